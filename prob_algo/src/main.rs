@@ -3,10 +3,30 @@ use std::io::{Write, BufWriter};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Instant;
-use plotters::prelude::*;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sorting_algo::bench::{self, plot_medians, screen_outliers, splitmix64, usage_error, Check, Config, DoublingLimits, Point, Summary};
+
+const USAGE: &str = "usage: prob_algo [--seed N] [--trials N] [--warmup N] [--outliers tukey|mad|off] [--remeasure] [doubling ...]";
 
 fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+    // Options are removed wherever they appear, so what is left is the
+    // subcommand and its positional arguments. The time budget only applies
+    // to the sorting benchmarks and keeps its default.
+    let config = Config::take_from(&mut args);
+    let seed = config.seed;
+
+    match args.get(1).map(String::as_str) {
+        None => {}
+        Some("doubling") => {
+            doubling(args.split_off(2), &config);
+            return;
+        }
+        Some(other) => usage_error(&format!("unexpected argument '{}'\n{}", other, USAGE)),
+    }
+    println!("Seed: {}", seed);
+
     // Define different sizes for each complexity type
     let sizes_n = vec![10_u128.pow(3), 10_u128.pow(4), 10_u128.pow(5), 10_u128.pow(6), 10_u128.pow(7), 10_u128.pow(8), 3*10_u128.pow(8)];
     let sizes_logn = vec![10_u128.pow(3), 10_u128.pow(4), 10_u128.pow(5), 10_u128.pow(6), 10_u128.pow(7), 10_u128.pow(8), 3*10_u128.pow(8)];
@@ -21,15 +41,15 @@ fn main() {
     let results = Arc::new(Mutex::new(Vec::new()));
     let mut handles = Vec::new();
 
-    for (complexity, sizes) in complexities {
+    for (index, (complexity, sizes)) in complexities.into_iter().enumerate() {
         let results = Arc::clone(&results);
 
         let handle = thread::spawn(move || {
-            let mut times = vec![(0, 0.0); sizes.len()];
-            for (index, &n) in sizes.iter().enumerate() {
-                let array = generate_random_array(n);
-                let (time_taken, _) = hire(&array, complexity);
-                times[index] = (n, time_taken.as_secs_f64());
+            // Each worker draws from its own stream, so the inputs do not depend on thread scheduling.
+            let mut rng = StdRng::seed_from_u64(splitmix64(seed ^ index as u64));
+            let mut times = Vec::with_capacity(sizes.len());
+            for &n in sizes.iter() {
+                times.push((n, measure_point(n, complexity, &mut rng, &config)));
                 println!("Complexity: {}, Size: {} -> Completed", complexity, n);
             }
             let mut results = results.lock().unwrap();
@@ -43,12 +63,47 @@ fn main() {
         handle.join().unwrap();
     }
 
-    save_results_to_file(&results.lock().unwrap(), "results.txt");
-    plot_all_results(&results.lock().unwrap());
+    save_results_to_file(&results.lock().unwrap(), seed, "results.txt");
+    plot_medians(&results.lock().unwrap(), "plot.png");
+}
+
+// doubling <n|logn|nlogn> [--start N] [--max-time SECONDS] [--max-memory MB]:
+// runs bench::doubling on one hiring workload and writes the steps to
+// doubling_results.txt.
+fn doubling(mut args: Vec<String>, config: &Config) {
+    let limits = DoublingLimits::take_from(&mut args);
+    let complexity = match args.as_slice() {
+        [complexity] if matches!(complexity.as_str(), "n" | "logn" | "nlogn") => complexity.as_str(),
+        _ => usage_error(&format!("usage: doubling <n|logn|nlogn> {}", DoublingLimits::USAGE)),
+    };
+    println!("Seed: {}", config.seed);
+
+    let mut rng = StdRng::seed_from_u64(splitmix64(config.seed));
+    let footprint = |n: usize| n * std::mem::size_of::<i32>();
+    let steps = bench::doubling(complexity, &limits, footprint, |n| Some(measure_point(n as u128, complexity, &mut rng, config)));
+    save_results_to_file(&[(complexity.to_string(), steps)], config.seed, "doubling_results.txt");
+}
+
+// Times `complexity` on fresh arrays of size `n` and summarises the trials.
+// Outlying trials are re-timed on new arrays when asked to; any still outlying
+// afterwards are left out of the summary. The hiring workloads produce no
+// output to check, so every trial counts as passing.
+fn measure_point(n: u128, complexity: &str, rng: &mut StdRng, config: &Config) -> Summary {
+    // A fresh array per trial, so the spread covers the input as well as the timer.
+    let mut trial = || {
+        let array = generate_random_array(n, rng);
+        hire(&array, complexity).0.as_secs_f64()
+    };
+    for _ in 0..config.warmup {
+        trial();
+    }
+    let mut points: Vec<Point> = (0..config.trials).map(|i| Point::new(n as usize, i, trial(), Check::Pass)).collect();
+    screen_outliers(&mut points, config, |_| (trial(), Check::Pass));
+    Summary::of_trials(&points)
 }
 
 fn hire(array: &[i32], complexity: &str) -> (std::time::Duration, i32) {
-    let mut hired = 0;
+    let hired = 0;
     let start = Instant::now();
     
     match complexity {
@@ -78,61 +133,29 @@ fn hiring_ologn(array: &[i32]) {
 fn hiring_onlogn(array: &[i32]) {
     let n = array.len() as i32;
     for _i in 0..n {
-        let mut count = 0;
+        let mut _count = 0;
         for _j in (1..=n).step_by(2) {
-            count = 1;
+            _count = 1;
         }
     }
 }
 
-fn generate_random_array(n: u128) -> Vec<i32> {
-    let mut rng = rand::thread_rng();
+fn generate_random_array(n: u128, rng: &mut impl Rng) -> Vec<i32> {
     (0..n).map(|_| rng.gen_range(0..1001)).collect()
 }
 
-fn save_results_to_file(results: &[(String, Vec<(u128, f64)>)], filename: &str) {
+fn save_results_to_file(results: &[(String, Vec<(u128, Summary)>)], seed: u64, filename: &str) {
     let file = File::create(filename).expect("Unable to create file");
     let mut writer = BufWriter::new(file);
 
+    writeln!(writer, "Seed: {}", seed).unwrap();
+    writeln!(writer).unwrap();
     for (complexity, times) in results {
         writeln!(writer, "Complexity: {}", complexity).unwrap();
-        for &(size, time) in times {
-            writeln!(writer, "{} {}", size, time).unwrap();
+        writeln!(writer, "# size trials mean median min stddev ci95 discarded").unwrap();
+        for &(size, s) in times {
+            writeln!(writer, "{} {} {} {} {} {} {} {}", size, s.trials, s.mean, s.median, s.min, s.stddev, s.ci95, s.discarded).unwrap();
         }
         writeln!(writer).unwrap();
     }
 }
-
-fn plot_all_results(results: &[(String, Vec<(u128, f64)>)]) {
-    let root = BitMapBackend::new("plot.png", (640, 480)).into_drawing_area();
-    root.fill(&WHITE).unwrap();
-
-    let (min_size, max_size) = (10_u128.pow(3) as i64, 3*10_u128.pow(8) as i64); 
-    let min_time = 0.0;
-    let max_time = results.iter()
-        .flat_map(|(_, times)| times.iter().map(|&(_, time)| time))
-        .fold(0.0, f64::max);
-
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Time Complexity Comparisons", ("sans-serif", 20))
-        .x_label_area_size(30)
-        .y_label_area_size(30)
-        .margin(5)
-        .build_cartesian_2d((min_size..max_size).log_scale(), (min_time..max_time).log_scale())
-        .unwrap();
-
-    chart.configure_mesh().x_desc("Size").y_desc("Time (s)").draw().unwrap();
-
-    let colors = vec![RED, GREEN, BLUE];
-    for (i, (complexity, times)) in results.iter().enumerate() {
-        let color = colors[i]; // Copy color for this iteration
-        chart.draw_series(LineSeries::new(
-            times.iter().map(|&(size, time)| (size as i64, time)),
-            &color,
-        )).unwrap()
-        .label(complexity)
-        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &color));
-    }
-
-    chart.configure_series_labels().border_style(&BLACK).draw().unwrap();
-}
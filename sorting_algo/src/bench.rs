@@ -0,0 +1,729 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use plotters::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::sorter::Sorter;
+
+/// Outcome of checking one sorter's output against a reference sort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    /// The output is out of order somewhere.
+    NotSorted,
+    /// The output is in order but is not a permutation of the input.
+    NotPermutation,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Check::Pass => "PASS",
+            Check::NotSorted => "FAIL (not sorted)",
+            Check::NotPermutation => "FAIL (not a permutation)",
+        })
+    }
+}
+
+/// Compares `output` with `expected`, the input sorted by a trusted sort such
+/// as `slice::sort_unstable`. Equality proves both order and permutation.
+pub fn verify<T: Ord>(output: &[T], expected: &[T]) -> Check {
+    if output == expected {
+        Check::Pass
+    } else if output.windows(2).any(|w| w[1] < w[0]) {
+        Check::NotSorted
+    } else {
+        Check::NotPermutation
+    }
+}
+
+/// Sorts a fresh copy of `input` with `sorter` and verifies it against
+/// `expected`. The copy is made before the clock starts, so every algorithm is
+/// timed on identical, unsorted data and pays nothing for the clone.
+/// Fails if the sorter refuses the input.
+pub fn measure<T: Ord + Clone>(sorter: &dyn Sorter<T>, input: &[T], expected: &[T]) -> Result<(f64, Check), Box<dyn Error>> {
+    let mut data = input.to_vec();
+    let start = Instant::now();
+    sorter.try_sort(&mut data)?;
+    let duration = start.elapsed().as_secs_f64();
+    Ok((duration, verify(&data, expected)))
+}
+
+/// Untimed runs before the first measurement, so page faults and cold caches
+/// are paid here rather than by trial 0.
+/// Returns the seconds they took, which still count against the time budget.
+pub fn warm_up<T: Ord + Clone>(sorter: &dyn Sorter<T>, input: &[T], runs: usize) -> Result<f64, Box<dyn Error>> {
+    let start = Instant::now();
+    for _ in 0..runs {
+        sorter.try_sort(&mut input.to_vec())?;
+    }
+    Ok(start.elapsed().as_secs_f64())
+}
+
+/// Settings shared by the benchmark modes, taken from the command line.
+#[derive(Clone, Copy, Debug)]
+pub struct Config {
+    pub seed: u64,
+    /// Inputs generated and timed per size; every point is summarised over them.
+    pub trials: usize,
+    /// Untimed runs per algorithm and size before the first trial.
+    pub warmup: usize,
+    pub outliers: OutlierRule,
+    /// Re-time outlying trials on their regenerated input instead of only
+    /// discarding them.
+    pub remeasure: bool,
+    /// Seconds each algorithm may spend over the whole run; sizes predicted to
+    /// overrun it are skipped.
+    pub budget: f64,
+}
+
+impl Config {
+    /// Takes the options shared by the benchmark binaries out of `args`:
+    /// `--seed`, `--trials`, `--warmup`, `--outliers` and `--remeasure`. The
+    /// budget is left at [`DEFAULT_BUDGET`]. Without `--seed` a fresh seed is
+    /// drawn, so the run can still be repeated from the value printed and saved.
+    pub fn take_from(args: &mut Vec<String>) -> Config {
+        let seed = take_option(args, "--seed", "an unsigned integer").unwrap_or_else(rand::random);
+        let trials = take_option(args, "--trials", "a positive count").unwrap_or(DEFAULT_TRIALS);
+        if trials == 0 {
+            usage_error("--trials expects a positive count");
+        }
+        let warmup = take_option(args, "--warmup", "a count of runs").unwrap_or(DEFAULT_WARMUP);
+        let outliers = take_option::<String>(args, "--outliers", "tukey, mad or off").map_or(OutlierRule::Tukey, |name| {
+            OutlierRule::parse(&name).unwrap_or_else(|| usage_error("--outliers expects tukey, mad or off"))
+        });
+        let remeasure = take_flag(args, "--remeasure");
+        Config { seed, trials, warmup, outliers, remeasure, budget: DEFAULT_BUDGET }
+    }
+}
+
+pub const DEFAULT_TRIALS: usize = 5;
+pub const DEFAULT_WARMUP: usize = 1;
+pub const DEFAULT_BUDGET: f64 = 600.0;
+/// Rounds of re-timing before a trial that is still outlying is discarded.
+pub const MAX_REMEASURES: usize = 3;
+
+/// How trials that disagree with the rest of their point are detected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutlierRule {
+    Off,
+    /// Outside `[Q1 - 1.5 IQR, Q3 + 1.5 IQR]`.
+    Tukey,
+    /// Modified z-score `0.6745 (x - median) / MAD` above 3.5 in magnitude.
+    Mad,
+}
+
+impl OutlierRule {
+    pub fn parse(name: &str) -> Option<OutlierRule> {
+        match name.to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(OutlierRule::Off),
+            "tukey" | "iqr" => Some(OutlierRule::Tukey),
+            "mad" => Some(OutlierRule::Mad),
+            _ => None,
+        }
+    }
+
+    /// Marks each sample that the rule rejects. Fewer than four samples are too
+    /// few to tell noise from signal, so nothing is rejected then.
+    pub fn flag(&self, samples: &[f64]) -> Vec<bool> {
+        if samples.len() < 4 {
+            return vec![false; samples.len()];
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        match self {
+            OutlierRule::Off => vec![false; samples.len()],
+            OutlierRule::Tukey => {
+                let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+                let (low, high) = (q1 - 1.5 * (q3 - q1), q3 + 1.5 * (q3 - q1));
+                samples.iter().map(|&t| t < low || t > high).collect()
+            }
+            OutlierRule::Mad => {
+                let median = quantile(&sorted, 0.5);
+                let mut deviations: Vec<f64> = samples.iter().map(|t| (t - median).abs()).collect();
+                deviations.sort_by(f64::total_cmp);
+                let mad = quantile(&deviations, 0.5);
+                if mad == 0.0 {
+                    return vec![false; samples.len()];
+                }
+                samples.iter().map(|t| (0.6745 * (t - median) / mad).abs() > 3.5).collect()
+            }
+        }
+    }
+}
+
+/// Linearly interpolated quantile of already sorted samples.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (below, above) = (position.floor() as usize, position.ceil() as usize);
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+}
+
+/// Applies `config.outliers` to the trials of one point. With
+/// `config.remeasure`, outlying trials are first re-timed through `remeasure`,
+/// which gets the trial number, for up to [`MAX_REMEASURES`] rounds. Trials
+/// still outlying are marked; the count of them is returned.
+pub fn screen_outliers(
+    points: &mut [Point],
+    config: &Config,
+    mut remeasure: impl FnMut(usize) -> (f64, Check),
+) -> usize {
+    let rounds = if config.remeasure { MAX_REMEASURES } else { 0 };
+    for round in 0..=rounds {
+        let times: Vec<f64> = points.iter().map(|p| p.time).collect();
+        let flags = config.outliers.flag(&times);
+        for (point, &flagged) in points.iter_mut().zip(&flags) {
+            point.outlier = flagged;
+        }
+        if round == rounds || !flags.contains(&true) {
+            break;
+        }
+        for point in points.iter_mut().filter(|p| p.outlier) {
+            let (time, check) = remeasure(point.trial);
+            point.time = time;
+            point.remeasured += 1;
+            // A failure on any run of the trial must stay visible.
+            if point.check == Check::Pass {
+                point.check = check;
+            }
+        }
+    }
+    points.iter().filter(|p| p.outlier).count()
+}
+
+/// Generator for one benchmark input. Its stream depends only on the run's
+/// seed and the input's size and trial, so a single point can be regenerated
+/// without replaying the rest of the run.
+pub fn input_rng(seed: u64, size: usize, trial: usize) -> StdRng {
+    StdRng::seed_from_u64(splitmix64(splitmix64(seed ^ size as u64) ^ trial as u64))
+}
+
+/// SplitMix64 finaliser; turns related seeds into unrelated ones.
+pub fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[derive(Clone, Debug)]
+pub struct Point {
+    pub size: usize,
+    /// Which of the inputs generated for this size was used; together with the
+    /// size it identifies the exact input.
+    pub trial: usize,
+    pub time: f64,
+    pub check: Check,
+    /// Rejected by the outlier rule and left out of the summary.
+    pub outlier: bool,
+    /// How many times the trial was re-timed after looking like an outlier.
+    pub remeasured: usize,
+}
+
+impl Point {
+    pub fn new(size: usize, trial: usize, time: f64, check: Check) -> Self {
+        Point { size, trial, time, check, outlier: false, remeasured: 0 }
+    }
+}
+
+/// Every measurement for one algorithm.
+#[derive(Clone, Debug)]
+pub struct Series {
+    pub name: String,
+    pub points: Vec<Point>,
+    /// Sizes that were not measured, with the reason.
+    pub skipped: Vec<(usize, String)>,
+    /// Seconds spent on this algorithm so far, warm-up and re-timing included.
+    pub spent: f64,
+}
+
+impl Series {
+    pub fn new(name: impl Into<String>) -> Self {
+        Series { name: name.into(), points: Vec::new(), skipped: Vec::new(), spent: 0.0 }
+    }
+
+    /// Predicts the median time of one trial at `size` by extrapolating from
+    /// the two largest sizes measured so far, as `t * (size / n)^k` with `k`
+    /// the growth exponent between them. The exponent is clamped to `1..=3`,
+    /// since timings of small sizes are noisy; with a single size it is 2, so
+    /// an algorithm of unknown growth is not let loose on a large jump. `None`
+    /// before anything has been measured.
+    pub fn predict(&self, size: usize) -> Option<f64> {
+        predict(&self.summaries(), size)
+    }
+
+    /// Whether running `runs` trials at `size` fits in `budget` seconds given
+    /// what has been spent; the reason for skipping it otherwise.
+    pub fn check_budget(&self, size: usize, runs: usize, budget: f64) -> Result<(), String> {
+        match self.predict(size) {
+            Some(trial) if self.spent + trial * runs as f64 > budget => Err(format!(
+                "predicted {:.3} s for {} runs would exceed the {} s budget ({:.3} s spent)",
+                trial * runs as f64, runs, budget, self.spent
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.points.iter().all(|p| p.check == Check::Pass)
+    }
+
+    /// Points grouped by size, in the order the sizes were measured.
+    pub fn by_size(&self) -> impl Iterator<Item = &[Point]> {
+        self.points.chunk_by(|a, b| a.size == b.size)
+    }
+
+    /// The trials of `size` when it is the most recently measured size, and
+    /// nothing otherwise.
+    pub fn trials_mut(&mut self, size: usize) -> &mut [Point] {
+        let start = self.points.iter().rposition(|p| p.size != size).map_or(0, |i| i + 1);
+        &mut self.points[start..]
+    }
+
+    /// `(size, summary of its trials)` pairs in the shape the plotting code
+    /// takes. Outliers are left out of the statistics and counted instead.
+    pub fn summaries(&self) -> Vec<(u128, Summary)> {
+        self.by_size().map(|points| (points[0].size as u128, Summary::of_trials(points))).collect()
+    }
+}
+
+/// Predicts the median time of one trial at `size` from `(size, summary)`
+/// points in increasing size; see [`Series::predict`].
+pub fn predict(summaries: &[(u128, Summary)], size: usize) -> Option<f64> {
+    let (n, last) = summaries.last()?;
+    let exponent = match summaries.len().checked_sub(2).map(|i| summaries[i]) {
+        Some((m, previous)) if previous.median > 0.0 && last.median > 0.0 => {
+            ((last.median / previous.median).ln() / (*n as f64 / m as f64).ln()).clamp(1.0, 3.0)
+        }
+        _ => 2.0,
+    };
+    Some(last.median * (size as f64 / *n as f64).powf(exponent))
+}
+
+/// Least-squares slope of `ln(time)` against `ln(size)`, i.e. the `k` in
+/// `T(n) ~ n^k`. Points with a zero time are ignored; `None` when fewer than
+/// two remain.
+pub fn growth_exponent(points: &[(u128, f64)]) -> Option<f64> {
+    let logs: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|&&(_, time)| time > 0.0)
+        .map(|&(size, time)| ((size as f64).ln(), time.ln()))
+        .collect();
+    if logs.len() < 2 {
+        return None;
+    }
+    let n = logs.len() as f64;
+    let (mean_x, mean_y) = (logs.iter().map(|p| p.0).sum::<f64>() / n, logs.iter().map(|p| p.1).sum::<f64>() / n);
+    let covariance: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    Some(covariance / variance)
+}
+
+/// Statistics over the trials of one point. `ci95` is the half-width of the
+/// 95% confidence interval for the mean, using Student's t for small samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub trials: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub min: f64,
+    pub stddev: f64,
+    pub ci95: f64,
+    /// Trials rejected as outliers and not counted in `trials`.
+    pub discarded: usize,
+}
+
+impl Summary {
+    /// Summarises at least one sample; a single sample has zero spread.
+    pub fn of(samples: &[f64]) -> Summary {
+        assert!(!samples.is_empty(), "cannot summarise zero samples");
+        let n = samples.len();
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let median = quantile(&sorted, 0.5);
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let stddev = if n > 1 {
+            (samples.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        let ci95 = if n > 1 { t_critical(n - 1) * stddev / (n as f64).sqrt() } else { 0.0 };
+        Summary { trials: n, mean, median, min: sorted[0], stddev, ci95, discarded: 0 }
+    }
+
+    /// Summarises the trials of one point, leaving out and counting the ones
+    /// marked as outliers. At least one trial must be kept.
+    pub fn of_trials(points: &[Point]) -> Summary {
+        let times: Vec<f64> = points.iter().filter(|p| !p.outlier).map(|p| p.time).collect();
+        let mut summary = Summary::of(&times);
+        summary.discarded = points.len() - times.len();
+        summary
+    }
+}
+
+/// Two-sided 95% critical value of Student's t distribution.
+fn t_critical(degrees_of_freedom: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+        2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+        2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+    ];
+    TABLE.get(degrees_of_freedom.wrapping_sub(1)).copied().unwrap_or(1.96)
+}
+
+/// Writes every series under a `Seed:` header; rerunning with `--seed` and
+/// that value reproduces the inputs.
+pub fn write_results(path: impl AsRef<Path>, seed: u64, results: &[Series]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "Seed: {}", seed)?;
+    for series in results {
+        writeln!(file, "Algorithm: {}", series.name)?;
+        for (points, (_, summary)) in series.by_size().zip(series.summaries()) {
+            for point in points {
+                write!(
+                    file,
+                    "Size: {}, Input: {}#{}, Time: {}, Check: {}",
+                    point.size, point.size, point.trial, point.time, point.check
+                )?;
+                if point.remeasured > 0 {
+                    write!(file, ", Remeasured: {}", point.remeasured)?;
+                }
+                if point.outlier {
+                    write!(file, ", Outlier: yes")?;
+                }
+                writeln!(file)?;
+            }
+            writeln!(
+                file,
+                "Size: {}, Trials: {}, Mean: {}, Median: {}, Min: {}, StdDev: {}, CI95: {}, Discarded: {}",
+                points[0].size, summary.trials, summary.mean, summary.median, summary.min, summary.stddev, summary.ci95,
+                summary.discarded
+            )?;
+        }
+        for (size, reason) in &series.skipped {
+            writeln!(file, "Size: {}, Skipped: {}", size, reason)?;
+        }
+    }
+    Ok(())
+}
+
+/// The series fit for plotting; failing ones are reported and left out so a
+/// broken algorithm cannot produce a convincing curve.
+pub fn plottable(results: &[Series]) -> Vec<(String, Vec<(u128, Summary)>)> {
+    results
+        .iter()
+        .filter(|series| {
+            if !series.passed() {
+                eprintln!("Not plotting {}: it failed the correctness check", series.name);
+            }
+            series.passed()
+        })
+        .map(|series| (series.name.clone(), series.summaries()))
+        .collect()
+}
+
+/// Prints `message` and exits with status 2, for mistakes on the command line.
+pub fn usage_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(2);
+}
+
+/// Removes `flag VALUE` from the arguments and parses the value, exiting with
+/// `expects` as the message when it is missing or malformed.
+pub fn take_option<T: std::str::FromStr>(args: &mut Vec<String>, flag: &str, expects: &str) -> Option<T> {
+    let i = args.iter().position(|arg| arg == flag)?;
+    let value = args
+        .get(i + 1)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| usage_error(&format!("{} expects {}", flag, expects)));
+    args.drain(i..i + 2);
+    Some(value)
+}
+
+/// Removes `flag` from the arguments, returning whether it was there.
+pub fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let position = args.iter().position(|arg| arg == flag);
+    position.map(|i| args.remove(i)).is_some()
+}
+
+/// When a doubling experiment stops.
+#[derive(Clone, Copy, Debug)]
+pub struct DoublingLimits {
+    pub start: usize,
+    /// Seconds one trial of the next size may be predicted to take.
+    pub max_time: f64,
+    /// Megabytes the next size may need.
+    pub max_memory_mb: usize,
+}
+
+impl DoublingLimits {
+    pub const USAGE: &'static str = "[--start N] [--max-time SECONDS] [--max-memory MB]";
+
+    /// Takes `--start`, `--max-time` and `--max-memory` out of `args`.
+    pub fn take_from(args: &mut Vec<String>) -> DoublingLimits {
+        let start = take_option(args, "--start", "a positive size").unwrap_or(1024);
+        if start == 0 {
+            usage_error("--start expects a positive size");
+        }
+        let max_time: f64 = take_option(args, "--max-time", "a positive number of seconds").unwrap_or(10.0);
+        if max_time.is_nan() || max_time <= 0.0 {
+            usage_error("--max-time expects a positive number of seconds");
+        }
+        let max_memory_mb = take_option(args, "--max-memory", "a size in megabytes").unwrap_or(1024);
+        DoublingLimits { start, max_time, max_memory_mb }
+    }
+}
+
+/// Doubles n from `limits.start`, summarising each size with `step` and
+/// printing T(n)/T(n/2) and its exponent log2 of that ratio, until the next
+/// size is predicted by [`predict`] to take longer than the time limit per
+/// trial, its `footprint` in bytes would pass the memory limit, or `step`
+/// returns `None`. The overall exponent of `name` is fitted to the upper half
+/// of the steps, where fixed overheads matter least. Returns every step.
+pub fn doubling(
+    name: &str,
+    limits: &DoublingLimits,
+    footprint: impl Fn(usize) -> usize,
+    mut step: impl FnMut(usize) -> Option<Summary>,
+) -> Vec<(u128, Summary)> {
+    let mut steps: Vec<(u128, Summary)> = Vec::new();
+    let mut size = limits.start;
+    loop {
+        if footprint(size) > limits.max_memory_mb << 20 {
+            println!(
+                "Stopping before n = {}: about {} MB would pass the {} MB limit",
+                size, footprint(size) >> 20, limits.max_memory_mb
+            );
+            break;
+        }
+        let Some(summary) = step(size) else { break };
+        match steps.last().map(|(_, previous)| summary.median / previous.median) {
+            Some(ratio) => println!(
+                "n = {}: T(n) = {} s, T(n)/T(n/2) = {:.3}, exponent {:.3}",
+                size, summary.median, ratio, ratio.log2()
+            ),
+            None => println!("n = {}: T(n) = {} s", size, summary.median),
+        }
+        steps.push((size as u128, summary));
+
+        let Some(next) = size.checked_mul(2) else { break };
+        let predicted = predict(&steps, next).unwrap_or(0.0);
+        if predicted > limits.max_time {
+            println!("Stopping before n = {}: predicted {:.3} s per trial would pass the {} s limit", next, predicted, limits.max_time);
+            break;
+        }
+        size = next;
+    }
+
+    let medians: Vec<(u128, f64)> = steps.iter().map(|&(n, s)| (n, s.median)).collect();
+    match growth_exponent(&medians[medians.len() / 2..]).or_else(|| growth_exponent(&medians)) {
+        Some(exponent) => println!("{}: T(n) grows as about n^{:.3}", name, exponent),
+        None => println!("{}: too few steps to infer a growth exponent", name),
+    }
+    steps
+}
+
+/// Draws the median of each point's trials, with error bars spanning the 95%
+/// confidence interval of the mean.
+pub fn plot_medians(results: &[(String, Vec<(u128, Summary)>)], filename: &str) {
+    let root = BitMapBackend::new(filename, (640, 480)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+
+    let (min_size, max_size) = (10_u128.pow(3) as i64, 3*10_u128.pow(8) as i64);
+    let min_time = 0.0;
+    let max_time = results.iter()
+        .flat_map(|(_, points)| points.iter().map(|(_, s)| (s.mean + s.ci95).max(s.median)))
+        .fold(0.0, f64::max);
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Time Complexity Comparisons", ("sans-serif", 20))
+        .x_label_area_size(30)
+        .y_label_area_size(30)
+        .margin(5)
+        .build_cartesian_2d((min_size..max_size).log_scale(), (min_time..max_time).log_scale())
+        .unwrap();
+
+    chart.configure_mesh().x_desc("Size").y_desc("Time (s)").draw().unwrap();
+
+    for (i, (sorting, points)) in results.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        chart.draw_series(LineSeries::new(
+            points.iter().map(|&(size, s)| (size as i64, s.median)),
+            &color,
+        )).unwrap()
+        .label(sorting)
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+        // The lower end is clamped to the smallest sample, which keeps it positive on the log axis.
+        chart.draw_series(points.iter().filter(|(_, s)| s.trials > 1).map(|&(size, s)| {
+            ErrorBar::new_vertical(size as i64, (s.mean - s.ci95).max(s.min), s.median, s.mean + s.ci95, color, 4)
+        })).unwrap();
+    }
+
+    chart.configure_series_labels().border_style(BLACK).draw().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn summary_of_five_samples() {
+        let summary = Summary::of(&[4.0, 1.0, 5.0, 3.0, 2.0]);
+        assert_eq!(summary.trials, 5);
+        assert_close(summary.mean, 3.0);
+        assert_close(summary.median, 3.0);
+        assert_close(summary.min, 1.0);
+        // Sample (n - 1) variance: (4 + 1 + 0 + 1 + 4) / 4.
+        assert_close(summary.stddev, 2.5f64.sqrt());
+        assert_close(summary.ci95, 2.776 * 2.5f64.sqrt() / 5f64.sqrt());
+        assert_eq!(summary.discarded, 0);
+    }
+
+    #[test]
+    fn summary_of_one_sample_has_no_spread() {
+        let summary = Summary::of(&[0.25]);
+        assert_eq!(summary.trials, 1);
+        assert_eq!((summary.mean, summary.median, summary.min), (0.25, 0.25, 0.25));
+        assert_eq!((summary.stddev, summary.ci95), (0.0, 0.0));
+    }
+
+    #[test]
+    fn summary_median_of_even_count_interpolates() {
+        assert_close(Summary::of(&[1.0, 2.0, 4.0, 10.0]).median, 3.0);
+    }
+
+    #[test]
+    fn t_critical_uses_the_table_then_the_normal_limit() {
+        assert_eq!(t_critical(1), 12.706);
+        assert_eq!(t_critical(4), 2.776);
+        assert_eq!(t_critical(30), 2.042);
+        assert_eq!(t_critical(31), 1.96);
+        assert_eq!(t_critical(1000), 1.96);
+    }
+
+    const PLANTED: [f64; 6] = [1.0, 1.1, 0.9, 1.0, 1.05, 10.0];
+
+    #[test]
+    fn tukey_and_mad_flag_a_planted_outlier() {
+        for rule in [OutlierRule::Tukey, OutlierRule::Mad] {
+            assert_eq!(rule.flag(&PLANTED), [false, false, false, false, false, true], "{:?}", rule);
+            assert_eq!(rule.flag(&[2.0, 1.0, 3.0, 2.0, 1.0]), [false; 5], "{:?}", rule);
+        }
+        assert_eq!(OutlierRule::Off.flag(&PLANTED), [false; 6]);
+    }
+
+    #[test]
+    fn mad_of_zero_flags_nothing() {
+        // Most samples are identical, so the MAD is 0 and no scale is known.
+        assert_eq!(OutlierRule::Mad.flag(&[1.0, 1.0, 1.0, 1.0, 5.0]), [false; 5]);
+        // Tukey has the same degenerate IQR but rejects anything off it.
+        assert_eq!(OutlierRule::Tukey.flag(&[1.0, 1.0, 1.0, 1.0, 5.0]), [false, false, false, false, true]);
+    }
+
+    #[test]
+    fn fewer_than_four_samples_are_never_flagged() {
+        for rule in [OutlierRule::Tukey, OutlierRule::Mad] {
+            assert_eq!(rule.flag(&[1.0, 1.0, 100.0]), [false; 3], "{:?}", rule);
+            assert_eq!(rule.flag(&[]), [false; 0], "{:?}", rule);
+        }
+    }
+
+    fn planted_points(check: Check) -> Vec<Point> {
+        let mut points: Vec<Point> = PLANTED.iter().enumerate().map(|(trial, &time)| Point::new(8, trial, time, Check::Pass)).collect();
+        points[5].check = check;
+        points
+    }
+
+    fn config(outliers: OutlierRule, remeasure: bool) -> Config {
+        Config { seed: 0, trials: PLANTED.len(), warmup: 0, outliers, remeasure, budget: DEFAULT_BUDGET }
+    }
+
+    #[test]
+    fn screening_without_remeasure_only_marks() {
+        let mut points = planted_points(Check::Pass);
+        let count = screen_outliers(&mut points, &config(OutlierRule::Tukey, false), |_| panic!("nothing may be re-timed"));
+        assert_eq!(count, 1);
+        assert!(points[5].outlier && points[5].time == 10.0 && points[5].remeasured == 0);
+        assert_eq!(Summary::of_trials(&points).discarded, 1);
+    }
+
+    #[test]
+    fn remeasure_replaces_the_time_and_keeps_a_failure() {
+        let mut points = planted_points(Check::NotSorted);
+        let mut retimed = Vec::new();
+        let count = screen_outliers(&mut points, &config(OutlierRule::Mad, true), |trial| {
+            retimed.push(trial);
+            (1.02, Check::Pass)
+        });
+        assert_eq!(retimed, [5]);
+        assert_eq!(count, 0);
+        assert!(!points[5].outlier);
+        assert_eq!((points[5].time, points[5].remeasured), (1.02, 1));
+        // The passing re-run must not hide that the first run failed.
+        assert_eq!(points[5].check, Check::NotSorted);
+
+        // A re-run that fails is recorded on a trial that passed.
+        let mut points = planted_points(Check::Pass);
+        screen_outliers(&mut points, &config(OutlierRule::Tukey, true), |_| (1.02, Check::NotPermutation));
+        assert_eq!(points[5].check, Check::NotPermutation);
+    }
+
+    #[test]
+    fn remeasure_gives_up_after_max_rounds() {
+        let mut points = planted_points(Check::Pass);
+        let count = screen_outliers(&mut points, &config(OutlierRule::Tukey, true), |_| (20.0, Check::Pass));
+        assert_eq!(count, 1);
+        assert!(points[5].outlier);
+        assert_eq!((points[5].time, points[5].remeasured), (20.0, MAX_REMEASURES));
+    }
+
+    fn series(medians: &[(usize, f64)]) -> Series {
+        let mut series = Series::new("test");
+        for &(size, time) in medians {
+            series.points.push(Point::new(size, 0, time, Check::Pass));
+        }
+        series
+    }
+
+    #[test]
+    fn predict_extrapolates_the_last_two_sizes() {
+        assert_eq!(series(&[]).predict(1000), None);
+        assert_close(series(&[(1000, 1.0), (2000, 4.0)]).predict(4000).unwrap(), 16.0);
+        // Only the two largest sizes count.
+        assert_close(series(&[(500, 1.0), (1000, 1.0), (2000, 2.0)]).predict(8000).unwrap(), 8.0);
+    }
+
+    #[test]
+    fn predict_assumes_quadratic_growth_from_one_size() {
+        assert_close(series(&[(1000, 0.5)]).predict(4000).unwrap(), 8.0);
+    }
+
+    #[test]
+    fn predict_clamps_the_exponent() {
+        // Flat timings would give 0, wildly growing ones 10; 1 and 3 are used.
+        assert_close(series(&[(1000, 1.0), (2000, 1.0)]).predict(4000).unwrap(), 2.0);
+        assert_close(series(&[(1000, 0.001), (2000, 1.024)]).predict(4000).unwrap(), 8.192);
+    }
+
+    #[test]
+    fn check_budget_fails_once_the_prediction_overruns() {
+        let mut s = series(&[(1000, 1.0), (2000, 4.0)]);
+        s.spent = 10.0;
+        // 10 s spent + 5 runs * 16 s predicted = 90 s.
+        assert!(s.check_budget(4000, 5, 100.0).is_ok());
+        assert!(s.check_budget(4000, 5, 90.0).is_ok());
+        let reason = s.check_budget(4000, 5, 89.0).unwrap_err();
+        assert!(reason.contains("89 s budget"), "{}", reason);
+        assert!(series(&[]).check_budget(1 << 30, 5, 0.0).is_ok());
+    }
+}
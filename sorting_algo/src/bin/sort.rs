@@ -0,0 +1,333 @@
+// A small Unix-style `sort` built on the algorithms in this crate.
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process;
+
+use sorting_algo::cutoffs::{Cutoffs, CUTOFFS_FILE};
+use sorting_algo::merge::merge_sort_buffered_by;
+use sorting_algo::radix::RadixKey;
+use sorting_algo::sorter::{comparison_registry, registry_with_cutoffs, Registry};
+
+const USAGE: &str = "usage: sort [OPTIONS] [FILE...]
+
+Sorts lines from the given files (or stdin, also as '-') and writes them to stdout.
+
+  -a, --algo NAME    algorithm to use, e.g. merge, radix, bucket, introsort (default: merge)
+  -n, --numeric      compare keys as numbers instead of lexicographically
+  -r, --reverse      reverse the order
+  -u, --unique       output only the first line of each run of equal keys
+  -s, --stable       keep input order for equal keys instead of comparing whole lines
+  -k, --key N        sort on field N (1-based) instead of the whole line
+  -t, --separator C  fields are separated by C instead of runs of whitespace
+  -l, --list         list the algorithms usable with the given options; after -n,
+                     the ones that only work with -n are marked
+  -h, --help         show this help";
+
+struct Options {
+    algo: String,
+    numeric: bool,
+    reverse: bool,
+    unique: bool,
+    stable: bool,
+    key: Option<usize>,
+    separator: Option<char>,
+    list: bool,
+    files: Vec<String>,
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("sort: {}", message);
+    process::exit(2);
+}
+
+fn parse_args() -> Options {
+    let mut options = Options {
+        algo: "merge".to_string(),
+        numeric: false,
+        reverse: false,
+        unique: false,
+        stable: false,
+        key: None,
+        separator: None,
+        list: false,
+        files: Vec::new(),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().unwrap_or_else(|| fail(&format!("option {} needs a value", name)));
+        match arg.as_str() {
+            "-a" | "--algo" => options.algo = value(&arg),
+            "-n" | "--numeric" => options.numeric = true,
+            "-r" | "--reverse" => options.reverse = true,
+            "-u" | "--unique" => options.unique = true,
+            "-s" | "--stable" => options.stable = true,
+            "-k" | "--key" => {
+                let field = value(&arg).parse().ok().filter(|&n: &usize| n > 0);
+                options.key = Some(field.unwrap_or_else(|| fail("--key expects a field number starting at 1")));
+            }
+            "-t" | "--separator" => {
+                let sep = value(&arg);
+                let mut chars = sep.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => options.separator = Some(c),
+                    _ => fail("--separator expects a single character"),
+                }
+            }
+            "-l" | "--list" => options.list = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            // Combined short flags such as -nru.
+            flags if flags.len() > 2 && flags.starts_with('-') && !flags.starts_with("--")
+                && flags[1..].chars().all(|c| "nrus".contains(c)) =>
+            {
+                for c in flags[1..].chars() {
+                    match c {
+                        'n' => options.numeric = true,
+                        'r' => options.reverse = true,
+                        'u' => options.unique = true,
+                        _ => options.stable = true,
+                    }
+                }
+            }
+            other if other.starts_with('-') && other != "-" => fail(&format!("unknown option '{}'\n\n{}", other, USAGE)),
+            _ => options.files.push(arg),
+        }
+    }
+    options
+}
+
+/// Prints the algorithms `--algo` accepts. Without `-n` only the comparison
+/// sorts can order lines; with it every sorter can, and the integer-only ones
+/// are marked.
+fn list_algorithms(options: &Options) {
+    let comparison: Registry<String> = comparison_registry(&Cutoffs::default());
+    if !options.numeric {
+        comparison.iter().for_each(|s| println!("{}", s.name()));
+        return;
+    }
+    let numeric: Registry<Numeric> = registry_with_cutoffs(&Cutoffs::default());
+    for sorter in numeric.iter() {
+        if comparison.lookup(sorter.name()).is_some() {
+            println!("{}", sorter.name());
+        } else {
+            println!("{} (-n only)", sorter.name());
+        }
+    }
+}
+
+fn read_lines(files: &[String]) -> io::Result<Vec<String>> {
+    let mut lines = Vec::new();
+    if files.is_empty() {
+        return io::stdin().lock().lines().collect();
+    }
+    for file in files {
+        let reader: Box<dyn BufRead> = if file == "-" {
+            Box::new(io::stdin().lock())
+        } else {
+            Box::new(BufReader::new(File::open(file).map_err(|e| {
+                io::Error::new(e.kind(), format!("{}: {}", file, e))
+            })?))
+        };
+        for line in reader.lines() {
+            lines.push(line?);
+        }
+    }
+    Ok(lines)
+}
+
+fn field<'a>(line: &'a str, options: &Options) -> &'a str {
+    let index = match options.key {
+        Some(n) => n - 1,
+        None => return line,
+    };
+    let found = match options.separator {
+        Some(sep) => line.split(sep).nth(index),
+        None => line.split_whitespace().nth(index),
+    };
+    found.unwrap_or("")
+}
+
+/// Maps a float onto a `u64` with the same ordering, so numeric keys can go
+/// through the radix sorts.
+fn float_key(value: f64) -> u64 {
+    let bits = value.to_bits();
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits | 1 << 63
+    }
+}
+
+/// The leading number of `text` as Unix `sort -n` reads it: blanks, an
+/// optional sign, digits and an optional fraction. Whatever follows is ignored.
+fn numeric_prefix(text: &str) -> &str {
+    let text = text.trim_start();
+    let bytes = text.as_bytes();
+    let mut end = usize::from(matches!(bytes.first(), Some(b'-' | b'+')));
+    while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+        end += 1;
+    }
+    if bytes.get(end) == Some(&b'.') {
+        end += 1;
+        while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+            end += 1;
+        }
+    }
+    &text[..end]
+}
+
+/// A line under numeric sorting. The integer sorts order it by `key` alone and
+/// are all stable; the comparison sorts also compare `index`. Either way equal
+/// keys keep their input order, whatever the algorithm.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Numeric {
+    key: u64,
+    index: usize,
+}
+
+impl RadixKey for Numeric {
+    fn radix_key(self) -> u128 {
+        self.key as u128
+    }
+}
+
+fn sort_numeric(lines: &[String], options: &Options) -> Vec<usize> {
+    let texts: Vec<&str> = lines.iter().map(|line| numeric_prefix(field(line, options))).collect();
+    // Fields without a leading number count as zero, as in Unix sort.
+    let integral = texts.iter().all(|t| t.parse::<i64>().is_ok() || t.parse::<f64>().is_err());
+    let keys: Vec<u64> = texts
+        .iter()
+        .map(|t| {
+            let key = if integral {
+                t.parse::<i64>().unwrap_or(0).radix_key() as u64
+            } else {
+                // Adding zero turns -0.0 into 0.0 so the two compare equal.
+                float_key(t.parse::<f64>().unwrap_or(0.0) + 0.0)
+            };
+            if options.reverse { !key } else { key }
+        })
+        .collect();
+    let mut records: Vec<Numeric> = keys.iter().enumerate().map(|(index, &key)| Numeric { key, index }).collect();
+
+    let registry: Registry<Numeric> = registry_with_cutoffs(&Cutoffs::load_or_default(CUTOFFS_FILE));
+    let sorter = registry
+        .lookup(&options.algo)
+        .unwrap_or_else(|| fail(&format!("unknown algorithm '{}', see --list", options.algo)));
+    sorter.try_sort(&mut records).unwrap_or_else(|e| fail(&e.to_string()));
+
+    let same_key = |a: &Numeric, b: &Numeric| a.key == b.key;
+    if !options.stable {
+        // Last-resort comparison on the whole line, like Unix sort without -s.
+        for group in records.chunk_by_mut(same_key) {
+            merge_sort_buffered_by(group, |a, b| {
+                let order = lines[a.index].cmp(&lines[b.index]);
+                if options.reverse { order.reverse() } else { order }
+            });
+        }
+    }
+    if options.unique {
+        records.dedup_by(|a, b| same_key(a, b));
+    }
+    records.iter().map(|r| r.index).collect()
+}
+
+/// A line under lexicographic sorting. Carries the options so its `Ord`, which
+/// the comparison sorts need, can apply direction and tie-breaking.
+#[derive(Clone)]
+struct Lex<'a> {
+    key: &'a str,
+    line: &'a str,
+    index: usize,
+    options: &'a Options,
+}
+
+impl PartialEq for Lex<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Lex<'_> {}
+
+impl PartialOrd for Lex<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Lex<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let mut order = self.key.cmp(other.key);
+        if order == Ordering::Equal && !self.options.stable {
+            order = self.line.cmp(other.line);
+        }
+        if self.options.reverse {
+            order = order.reverse();
+        }
+        order.then(self.index.cmp(&other.index))
+    }
+}
+
+fn sort_lexicographic(lines: &[String], options: &Options) -> Vec<usize> {
+    let mut records: Vec<Lex> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| Lex { key: field(line, options), line, index, options })
+        .collect();
+
+    let registry: Registry<Lex> = comparison_registry(&Cutoffs::load_or_default(CUTOFFS_FILE));
+    match registry.lookup(&options.algo) {
+        Some(sorter) => sorter.sort(&mut records),
+        None => fail(&format!(
+            "algorithm '{}' is unknown or needs integer keys; use -n or pick a comparison sort",
+            options.algo
+        )),
+    }
+
+    if options.unique {
+        records.dedup_by(|a, b| a.key == b.key);
+    }
+    records.iter().map(|r| r.index).collect()
+}
+
+fn main() -> io::Result<()> {
+    let options = parse_args();
+    if options.list {
+        list_algorithms(&options);
+        return Ok(());
+    }
+    let lines = read_lines(&options.files).unwrap_or_else(|e| fail(&e.to_string()));
+
+    let order = if options.numeric {
+        sort_numeric(&lines, &options)
+    } else {
+        sort_lexicographic(&lines, &options)
+    };
+
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    for index in order {
+        writeln!(out, "{}", lines[index])?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::numeric_prefix;
+
+    #[test]
+    fn numeric_prefix_stops_at_trailing_text() {
+        assert_eq!(numeric_prefix("10 apples"), "10");
+        assert_eq!(numeric_prefix("  -3.25kg"), "-3.25");
+        assert_eq!(numeric_prefix("+7/usr"), "+7");
+        assert_eq!(numeric_prefix("5."), "5.");
+        assert_eq!(numeric_prefix(".5x"), ".5");
+        assert_eq!(numeric_prefix("1e5"), "1");
+        assert_eq!(numeric_prefix("pears"), "");
+        assert_eq!(numeric_prefix("-"), "-");
+    }
+}
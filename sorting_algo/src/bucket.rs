@@ -0,0 +1,296 @@
+use crate::radix::RadixKey;
+use crate::selection::{selection_sort_by, SelectionSort};
+use crate::sorter::Sorter;
+
+pub fn bucket_sort<T: RadixKey + Ord>(array: &mut [T], k: usize) {
+    bucket_sort_with(array, k, &SelectionSort);
+}
+
+/// Distributes values into `k` equal-width buckets between the minimum and
+/// maximum key, then sorts each bucket with `inner`. Works on the full range of
+/// any `RadixKey` type without overflowing.
+pub fn bucket_sort_with<T, S>(array: &mut [T], k: usize, inner: &S)
+where
+    T: RadixKey,
+    S: Sorter<T> + ?Sized,
+{
+    if array.len() < 2 {
+        return;
+    }
+    let k = k.max(1);
+    let min_key = array.iter().map(|x| x.radix_key()).min().unwrap();
+    let max_key = array.iter().map(|x| x.radix_key()).max().unwrap();
+    // range < k * width, so every index lands in 0..k.
+    let width = (max_key - min_key) / k as u128 + 1;
+
+    let mut buckets: Vec<Vec<T>> = vec![Vec::new(); k];
+    for &value in array.iter() {
+        let index = ((value.radix_key() - min_key) / width) as usize;
+        buckets[index].push(value);
+    }
+    for bucket in &mut buckets {
+        inner.sort(bucket);
+    }
+    scatter(array, buckets);
+}
+
+/// Bucket sort for floats. With input uniform in `[0, 1)` this is the textbook
+/// expected-linear-time case; other ranges are normalized by min/max first.
+/// Infinities and NaNs are ordered as by `f64::total_cmp`, which is also how
+/// each bucket is selection sorted.
+pub fn bucket_sort_f64(array: &mut [f64], k: usize) {
+    bucket_sort_f64_by(array, k, |bucket| selection_sort_by(bucket, f64::total_cmp));
+}
+
+/// [`bucket_sort_f64`] with each bucket sorted by `inner`. The result is only
+/// ordered if `inner` orders a bucket as `f64::total_cmp` would, NaNs aside.
+pub fn bucket_sort_f64_with<S>(array: &mut [f64], k: usize, inner: &S)
+where
+    S: Sorter<f64> + ?Sized,
+{
+    bucket_sort_f64_by(array, k, |bucket| inner.sort(bucket));
+}
+
+fn bucket_sort_f64_by(array: &mut [f64], k: usize, mut sort_bucket: impl FnMut(&mut [f64])) {
+    if array.len() < 2 {
+        return;
+    }
+    let k = k.max(1);
+    let finite = array.iter().copied().filter(|x| x.is_finite());
+    let min_value = finite.clone().fold(f64::INFINITY, f64::min);
+    let max_value = finite.fold(f64::NEG_INFINITY, f64::max);
+
+    let mut buckets: Vec<Vec<f64>> = vec![Vec::new(); k];
+    for &value in array.iter() {
+        buckets[f64_bucket(value, min_value, max_value, k)].push(value);
+    }
+    for bucket in &mut buckets {
+        sort_bucket(bucket);
+    }
+    scatter(array, buckets);
+}
+
+/// Bucket of `value` among `k` equal-width buckets spanning the finite
+/// `min..=max`. When `max - min` overflows to infinity every operand is halved
+/// first, which keeps the ratio and cannot overflow.
+fn f64_bucket(value: f64, min: f64, max: f64, k: usize) -> usize {
+    if value.is_nan() {
+        return if value.is_sign_negative() { 0 } else { k - 1 };
+    }
+    let range = max - min;
+    let t = if range.is_finite() {
+        (value - min) / range
+    } else {
+        (value / 2.0 - min / 2.0) / (max / 2.0 - min / 2.0)
+    };
+    if range > 0.0 {
+        ((t * k as f64) as usize).min(k - 1)
+    } else if value > max {
+        k - 1
+    } else {
+        0
+    }
+}
+
+fn scatter<T: Copy>(array: &mut [T], buckets: Vec<Vec<T>>) {
+    let mut idx = 0;
+    for bucket in buckets {
+        for &value in bucket.iter() {
+            array[idx] = value;
+            idx += 1;
+        }
+    }
+}
+
+pub struct BucketSort<S = SelectionSort> {
+    pub buckets: usize,
+    pub inner: S,
+}
+
+impl BucketSort {
+    pub fn new(buckets: usize) -> Self {
+        BucketSort { buckets, inner: SelectionSort }
+    }
+}
+
+impl<T, S> Sorter<T> for BucketSort<S>
+where
+    T: RadixKey,
+    S: Sorter<T>,
+{
+    fn name(&self) -> &'static str {
+        match self.inner.name() {
+            "Selection Sort" => "Bucket Sort",
+            "Insertion Sort" => "Bucket Sort (insertion)",
+            "Merge Sort" => "Bucket Sort (merge)",
+            "Radix Sort" => "Bucket Sort (radix)",
+            _ => "Bucket Sort (custom)",
+        }
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        bucket_sort_with(data, self.buckets, &self.inner);
+    }
+
+    fn is_stable(&self) -> bool {
+        self.inner.is_stable()
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+/// Only the default inner sort gets this impl: `SelectionSort` and the other
+/// comparison sorts need `Ord`, which `f64` lacks, so buckets are sorted by
+/// `f64::total_cmp` instead. A generic impl over `S: Sorter<f64>` would overlap
+/// with this one, so pass such a sorter to [`bucket_sort_f64_with`].
+impl Sorter<f64> for BucketSort<SelectionSort> {
+    fn name(&self) -> &'static str {
+        "Bucket Sort"
+    }
+
+    fn sort(&self, data: &mut [f64]) {
+        bucket_sort_f64(data, self.buckets);
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::insertion::InsertionSort;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn check_i64(data: &[i64]) {
+        let mut expected = data.to_vec();
+        expected.sort();
+        for k in [1, 2, 10, 1000] {
+            let mut sorted = data.to_vec();
+            bucket_sort(&mut sorted, k);
+            assert_eq!(sorted, expected, "k = {}", k);
+            let mut sorted = data.to_vec();
+            bucket_sort_with(&mut sorted, k, &InsertionSort);
+            assert_eq!(sorted, expected, "k = {} with insertion sort", k);
+        }
+    }
+
+    fn check_f64(data: &[f64]) {
+        let mut expected = data.to_vec();
+        expected.sort_by(f64::total_cmp);
+        let bits = |v: &[f64]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        for k in [1, 2, 10, 1000] {
+            let mut sorted = data.to_vec();
+            bucket_sort_f64(&mut sorted, k);
+            assert_eq!(bits(&sorted), bits(&expected), "k = {}", k);
+        }
+    }
+
+    #[test]
+    fn empty_and_single_element() {
+        check_i64(&[]);
+        check_i64(&[42]);
+        check_f64(&[]);
+        check_f64(&[f64::NAN]);
+        let mut zero_buckets = vec![3, 1, 2];
+        bucket_sort(&mut zero_buckets, 0);
+        assert_eq!(zero_buckets, [1, 2, 3]);
+    }
+
+    #[test]
+    fn negative_values() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let data: Vec<i64> = (0..500).map(|_| rng.gen_range(-1000..1000)).collect();
+        check_i64(&data);
+        check_i64(&[-5, -1, -3, -3, -100]);
+    }
+
+    #[test]
+    fn full_i64_range_does_not_overflow() {
+        check_i64(&[i64::MAX, 0, i64::MIN, -1, 1, i64::MIN, i64::MAX]);
+        let mut rng = StdRng::seed_from_u64(6);
+        let data: Vec<i64> = (0..500).map(|_| rng.gen()).chain([i64::MIN, i64::MAX]).collect();
+        check_i64(&data);
+
+        let mut unsigned = vec![u64::MAX, 0, u64::MAX / 2, 1];
+        bucket_sort(&mut unsigned, 4);
+        assert_eq!(unsigned, [0, 1, u64::MAX / 2, u64::MAX]);
+    }
+
+    #[test]
+    fn floats_in_unit_interval() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let data: Vec<f64> = (0..1000).map(|_| rng.gen_range(0.0..1.0)).collect();
+        check_f64(&data);
+        check_f64(&[0.5, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn floats_whose_range_overflows() {
+        // max - min is infinite here, so normalising by the range is impossible.
+        check_f64(&[f64::MAX, -f64::MAX, 0.0, 1.0, -1.0, f64::MAX / 2.0]);
+        let mut rng = StdRng::seed_from_u64(8);
+        let data: Vec<f64> = (0..300).map(|_| rng.gen_range(-1.0..1.0) * f64::MAX).collect();
+        check_f64(&data);
+    }
+
+    #[test]
+    fn overflowing_range_still_spreads_over_the_buckets() {
+        let (min, max) = (-f64::MAX, f64::MAX);
+        let indices: Vec<usize> = [min, -f64::MAX / 2.0, 0.0, f64::MAX / 2.0, max]
+            .iter()
+            .map(|&v| f64_bucket(v, min, max, 4))
+            .collect();
+        assert_eq!(indices, [0, 1, 2, 3, 3]);
+        assert_eq!(f64_bucket(f64::INFINITY, min, max, 4), 3);
+        assert_eq!(f64_bucket(f64::NEG_INFINITY, min, max, 4), 0);
+    }
+
+    struct TotalCmpSort;
+
+    impl Sorter<f64> for TotalCmpSort {
+        fn name(&self) -> &'static str {
+            "Total Order Sort"
+        }
+
+        fn sort(&self, data: &mut [f64]) {
+            data.sort_by(f64::total_cmp);
+        }
+
+        fn is_stable(&self) -> bool {
+            true
+        }
+
+        fn is_in_place(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn floats_with_a_custom_inner_sort() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let data: Vec<f64> = (0..1000).map(|_| rng.gen_range(-1e6..1e6)).chain([f64::NAN, f64::NEG_INFINITY, f64::MAX]).collect();
+        let mut expected = data.clone();
+        expected.sort_by(f64::total_cmp);
+        for k in [1, 10, 1000] {
+            let mut sorted = data.clone();
+            bucket_sort_f64_with(&mut sorted, k, &TotalCmpSort);
+            assert!(sorted.iter().zip(&expected).all(|(a, b)| a.to_bits() == b.to_bits()), "k = {}", k);
+        }
+    }
+
+    #[test]
+    fn floats_with_nan_and_infinities() {
+        check_f64(&[1.0, f64::NAN, f64::NEG_INFINITY, -2.5, f64::INFINITY, -f64::NAN, 0.0, -0.0]);
+        check_f64(&[f64::INFINITY, f64::NEG_INFINITY]);
+        check_f64(&[f64::NAN, f64::NAN, 3.0]);
+    }
+}
@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::fmt;
+use std::mem::size_of;
+
+use crate::radix::{key_bounds, RadixKey};
+use crate::sorter::Sorter;
+
+/// Default cap on the bytes a counting or pigeonhole sort may spend on its
+/// per-key tables.
+pub const DEFAULT_MEMORY_BUDGET: usize = 1 << 30;
+
+/// Returned when the key range of the input would need a table larger than
+/// the memory budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeTooLarge {
+    pub range: u128,
+    pub required: u128,
+    pub budget: usize,
+}
+
+impl fmt::Display for RangeTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "key range of {} needs {} bytes, over the memory budget of {} bytes",
+            self.range, self.required, self.budget
+        )
+    }
+}
+
+impl Error for RangeTooLarge {}
+
+/// Detects the key range and checks a table of `slot_size` bytes per key fits
+/// in `budget`. Returns the minimum key and the number of slots.
+fn table_size<K: RadixKey>(keys: &[K], slot_size: usize, budget: usize) -> Result<Option<(u128, usize)>, RangeTooLarge> {
+    let (min, max) = match key_bounds(keys) {
+        Some(bounds) => bounds,
+        None => return Ok(None),
+    };
+    let range = max - min;
+    let required = range.saturating_add(1).saturating_mul(slot_size as u128);
+    if required > budget as u128 {
+        return Err(RangeTooLarge { range, required, budget });
+    }
+    Ok(Some((min, range as usize + 1)))
+}
+
+pub fn counting_sort<T: RadixKey>(data: &mut [T]) -> Result<(), RangeTooLarge> {
+    counting_sort_by_key(data, |&x| x, DEFAULT_MEMORY_BUDGET)
+}
+
+/// Stable counting sort of records by an integer key, so payloads travel with
+/// their keys. Uses one count per distinct key value between the minimum and
+/// maximum key, refusing inputs whose table would exceed `budget` bytes.
+pub fn counting_sort_by_key<T, K, F>(data: &mut [T], key: F, budget: usize) -> Result<(), RangeTooLarge>
+where
+    T: Clone,
+    K: RadixKey,
+    F: Fn(&T) -> K,
+{
+    let keys: Vec<K> = data.iter().map(&key).collect();
+    let (min, slots) = match table_size(&keys, size_of::<usize>(), budget)? {
+        Some(table) => table,
+        None => return Ok(()),
+    };
+
+    let mut counts = vec![0usize; slots];
+    for k in &keys {
+        counts[(k.radix_key() - min) as usize] += 1;
+    }
+    let mut offset = 0;
+    for c in counts.iter_mut() {
+        let count = *c;
+        *c = offset;
+        offset += count;
+    }
+
+    let input = data.to_vec();
+    for (item, k) in input.into_iter().zip(&keys) {
+        let slot = &mut counts[(k.radix_key() - min) as usize];
+        data[*slot] = item;
+        *slot += 1;
+    }
+    Ok(())
+}
+
+pub fn pigeonhole_sort<T: RadixKey>(data: &mut [T]) -> Result<(), RangeTooLarge> {
+    pigeonhole_sort_with_budget(data, DEFAULT_MEMORY_BUDGET)
+}
+
+/// Drops every value into a hole per possible key and reads the holes back in
+/// order. Simpler than counting sort but pays for a `Vec` per hole.
+pub fn pigeonhole_sort_with_budget<T: RadixKey>(data: &mut [T], budget: usize) -> Result<(), RangeTooLarge> {
+    let (min, slots) = match table_size(data, size_of::<Vec<T>>(), budget)? {
+        Some(table) => table,
+        None => return Ok(()),
+    };
+
+    let mut holes: Vec<Vec<T>> = vec![Vec::new(); slots];
+    for &x in data.iter() {
+        holes[(x.radix_key() - min) as usize].push(x);
+    }
+    for (slot, x) in data.iter_mut().zip(holes.into_iter().flatten()) {
+        *slot = x;
+    }
+    Ok(())
+}
+
+pub struct CountingSort {
+    pub memory_budget: usize,
+}
+
+impl<T: RadixKey> Sorter<T> for CountingSort {
+    fn name(&self) -> &'static str {
+        "Counting Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        if let Err(e) = self.try_sort(data) {
+            panic!("counting sort refused input: {}", e);
+        }
+    }
+
+    fn try_sort(&self, data: &mut [T]) -> Result<(), Box<dyn Error>> {
+        Ok(counting_sort_by_key(data, |&x| x, self.memory_budget)?)
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+pub struct PigeonholeSort {
+    pub memory_budget: usize,
+}
+
+impl<T: RadixKey> Sorter<T> for PigeonholeSort {
+    fn name(&self) -> &'static str {
+        "Pigeonhole Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        if let Err(e) = self.try_sort(data) {
+            panic!("pigeonhole sort refused input: {}", e);
+        }
+    }
+
+    fn try_sort(&self, data: &mut [T]) -> Result<(), Box<dyn Error>> {
+        Ok(pigeonhole_sort_with_budget(data, self.memory_budget)?)
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn try_sort_refuses_ranges_over_the_budget() {
+        let sorters: [&dyn Sorter<i64>; 2] =
+            [&CountingSort { memory_budget: 1 << 10 }, &PigeonholeSort { memory_budget: 1 << 10 }];
+        for sorter in sorters {
+            let mut narrow = vec![5, -3, 9, 0, 5];
+            sorter.try_sort(&mut narrow).unwrap();
+            assert_eq!(narrow, [-3, 0, 5, 5, 9]);
+
+            let mut wide = vec![3, i64::MAX, i64::MIN, 0];
+            let error = sorter.try_sort(&mut wide).unwrap_err();
+            assert!(error.downcast_ref::<RangeTooLarge>().is_some(), "{}: {}", sorter.name(), error);
+            assert_eq!(wide, [3, i64::MAX, i64::MIN, 0]);
+        }
+    }
+
+    #[test]
+    fn counting_sort_by_key_keeps_payload_order() {
+        let mut rng = StdRng::seed_from_u64(13);
+        // Few distinct keys, including negatives, so every key has many payloads.
+        let mut records: Vec<(i32, usize)> = (0..1000).map(|i| (rng.gen_range(-4..4), i)).collect();
+        let mut expected = records.clone();
+        expected.sort_by_key(|&(key, _)| key);
+        counting_sort_by_key(&mut records, |&(key, _)| key, DEFAULT_MEMORY_BUDGET).unwrap();
+        assert_eq!(records, expected);
+        for group in records.chunk_by(|a, b| a.0 == b.0) {
+            assert!(group.windows(2).all(|w| w[0].1 < w[1].1), "key {}", group[0].0);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Where `tune-cutoffs` stores the measured values, relative to the working directory.
+pub const CUTOFFS_FILE: &str = "cutoffs.cfg";
+
+/// Small-array thresholds below which the hybrid sorts switch to insertion sort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cutoffs {
+    pub merge: usize,
+    pub quick: usize,
+}
+
+impl Default for Cutoffs {
+    fn default() -> Self {
+        Cutoffs { merge: 32, quick: 16 }
+    }
+}
+
+impl Cutoffs {
+    /// Reads `key = value` lines; unknown keys are ignored and missing ones keep
+    /// their defaults.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut cutoffs = Cutoffs::default();
+        for line in fs::read_to_string(path)?.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("expected key = value, got '{}'", line))
+            })?;
+            let value: usize = value.trim().parse().map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("bad value for '{}': {}", key.trim(), e))
+            })?;
+            match key.trim() {
+                "merge" => cutoffs.merge = value,
+                "quick" => cutoffs.quick = value,
+                _ => {}
+            }
+        }
+        Ok(cutoffs)
+    }
+
+    /// Falls back to the defaults when the file is missing or unreadable.
+    pub fn load_or_default(path: impl AsRef<Path>) -> Self {
+        Self::load(path).unwrap_or_default()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = fs::File::create(path)?;
+        writeln!(file, "# Insertion-sort cutoffs measured by `sorting_algo tune-cutoffs`")?;
+        writeln!(file, "merge = {}", self.merge)?;
+        writeln!(file, "quick = {}", self.quick)?;
+        Ok(())
+    }
+}
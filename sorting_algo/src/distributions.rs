@@ -0,0 +1,215 @@
+use std::fmt;
+
+use rand::Rng;
+
+/// Shape of a generated benchmark input. Values are kept within `0..=n` so the
+/// counting-based sorts see the same key range as with uniform inputs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Distribution {
+    /// Uniform integers in `0..=n`.
+    Uniform,
+    Sorted,
+    Reverse,
+    /// Sorted, then `swaps` random pairs exchanged.
+    NearlySorted { swaps: usize },
+    /// Uniform over only `distinct` values spread across `0..=n`.
+    FewUnique { distinct: usize },
+    AllEqual,
+    /// Ascending to the middle, then descending.
+    OrganPipe,
+    /// `teeth` ascending runs, each covering the whole value range.
+    Sawtooth { teeth: usize },
+    /// Normal with mean n/2 and standard deviation n/8.
+    Gaussian,
+    /// Ranks `1..=n` with probability proportional to `1 / rank^exponent`.
+    Zipf { exponent: f64 },
+    /// Uniform over about sqrt(n) values, so every value repeats about sqrt(n) times.
+    DuplicateHeavy,
+}
+
+impl Distribution {
+    /// Every distribution with its default parameters.
+    pub fn all() -> Vec<Distribution> {
+        vec![
+            Distribution::Uniform,
+            Distribution::Sorted,
+            Distribution::Reverse,
+            Distribution::NearlySorted { swaps: 100 },
+            Distribution::FewUnique { distinct: 16 },
+            Distribution::AllEqual,
+            Distribution::OrganPipe,
+            Distribution::Sawtooth { teeth: 16 },
+            Distribution::Gaussian,
+            Distribution::Zipf { exponent: 1.1 },
+            Distribution::DuplicateHeavy,
+        ]
+    }
+
+    /// Name used on the command line and in file names.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Distribution::Uniform => "uniform",
+            Distribution::Sorted => "sorted",
+            Distribution::Reverse => "reverse",
+            Distribution::NearlySorted { .. } => "nearly-sorted",
+            Distribution::FewUnique { .. } => "few-unique",
+            Distribution::AllEqual => "all-equal",
+            Distribution::OrganPipe => "organ-pipe",
+            Distribution::Sawtooth { .. } => "sawtooth",
+            Distribution::Gaussian => "gaussian",
+            Distribution::Zipf { .. } => "zipf",
+            Distribution::DuplicateHeavy => "duplicate-heavy",
+        }
+    }
+
+    /// Parses `name` or `name:param`, e.g. `zipf:1.5` or `nearly-sorted:1000`.
+    /// A missing parameter takes the default from [`Distribution::all`].
+    pub fn parse(spec: &str) -> Result<Distribution, String> {
+        let (name, param) = match spec.split_once(':') {
+            Some((name, param)) => (name, Some(param)),
+            None => (spec, None),
+        };
+        let default = Distribution::all()
+            .into_iter()
+            .find(|d| d.slug().eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("unknown distribution '{}'", name))?;
+        let Some(param) = param else {
+            return Ok(default);
+        };
+        let bad = |e: &dyn fmt::Display| format!("bad parameter for '{}': {}", name, e);
+        Ok(match default {
+            Distribution::NearlySorted { .. } => Distribution::NearlySorted { swaps: param.parse().map_err(|e| bad(&e))? },
+            Distribution::FewUnique { .. } => Distribution::FewUnique { distinct: param.parse().map_err(|e| bad(&e))? },
+            Distribution::Sawtooth { .. } => Distribution::Sawtooth { teeth: param.parse().map_err(|e| bad(&e))? },
+            Distribution::Zipf { .. } => match param.parse::<f64>() {
+                Ok(exponent) if exponent > 0.0 => Distribution::Zipf { exponent },
+                Ok(_) => return Err(bad(&"exponent must be positive")),
+                Err(e) => return Err(bad(&e)),
+            },
+            _ => return Err(format!("distribution '{}' takes no parameter", name)),
+        })
+    }
+
+    pub fn generate<R: Rng + ?Sized>(&self, n: usize, rng: &mut R) -> Vec<i64> {
+        let top = n as i64;
+        match *self {
+            Distribution::Uniform => (0..n).map(|_| rng.gen_range(0..=top)).collect(),
+            Distribution::Sorted => (0..top).collect(),
+            Distribution::Reverse => (0..top).rev().collect(),
+            Distribution::NearlySorted { swaps } => {
+                let mut v: Vec<i64> = (0..top).collect();
+                if n > 1 {
+                    for _ in 0..swaps {
+                        v.swap(rng.gen_range(0..n), rng.gen_range(0..n));
+                    }
+                }
+                v
+            }
+            Distribution::FewUnique { distinct } => {
+                let distinct = distinct.max(1) as i64;
+                (0..n).map(|_| rng.gen_range(0..distinct) * top / distinct).collect()
+            }
+            Distribution::AllEqual => vec![top / 2; n],
+            Distribution::OrganPipe => (0..top).map(|i| i.min(top - 1 - i) * 2).collect(),
+            Distribution::Sawtooth { teeth } => {
+                let period = n.div_ceil(teeth.max(1)).max(1) as i64;
+                (0..top).map(|i| (i % period) * top / period).collect()
+            }
+            Distribution::Gaussian => {
+                let (mean, sd) = (top as f64 / 2.0, top as f64 / 8.0);
+                (0..n).map(|_| (mean + sd * standard_normal(rng)).round().clamp(0.0, top as f64) as i64).collect()
+            }
+            Distribution::Zipf { exponent } => {
+                let zipf = Zipf::new(n.max(1) as f64, exponent);
+                (0..n).map(|_| zipf.sample(rng)).collect()
+            }
+            Distribution::DuplicateHeavy => {
+                let distinct = ((n as f64).sqrt() as i64).max(1);
+                (0..n).map(|_| rng.gen_range(0..distinct) * top / distinct).collect()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Distribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Distribution::NearlySorted { swaps } => write!(f, "{}:{}", self.slug(), swaps),
+            Distribution::FewUnique { distinct } => write!(f, "{}:{}", self.slug(), distinct),
+            Distribution::Sawtooth { teeth } => write!(f, "{}:{}", self.slug(), teeth),
+            Distribution::Zipf { exponent } => write!(f, "{}:{}", self.slug(), exponent),
+            _ => f.write_str(self.slug()),
+        }
+    }
+}
+
+/// Box-Muller transform; one of the pair is discarded to keep the generator stateless.
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u1: f64 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+/// Zipf sampler over `1..=n` using rejection-inversion (Hörmann and Derflinger),
+/// which needs constant memory however large `n` is.
+struct Zipf {
+    n: f64,
+    s: f64,
+    h_x1: f64,
+    h_n: f64,
+    threshold: f64,
+}
+
+impl Zipf {
+    fn new(n: f64, s: f64) -> Self {
+        let mut zipf = Zipf { n, s, h_x1: 0.0, h_n: 0.0, threshold: 0.0 };
+        zipf.h_x1 = zipf.h_integral(1.5) - 1.0;
+        zipf.h_n = zipf.h_integral(n + 0.5);
+        zipf.threshold = 2.0 - zipf.h_integral_inv(zipf.h_integral(2.5) - zipf.h(2.0));
+        zipf
+    }
+
+    fn h(&self, x: f64) -> f64 {
+        (-self.s * x.ln()).exp()
+    }
+
+    /// Antiderivative of `h`, with the `s == 1` case taken as the limit `ln x`.
+    fn h_integral(&self, x: f64) -> f64 {
+        let log_x = x.ln();
+        helper((1.0 - self.s) * log_x) * log_x
+    }
+
+    fn h_integral_inv(&self, x: f64) -> f64 {
+        let t = (x * (1.0 - self.s)).max(-1.0);
+        (helper_inv(t) * x).exp()
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i64 {
+        loop {
+            let u = self.h_n + rng.gen::<f64>() * (self.h_x1 - self.h_n);
+            let x = self.h_integral_inv(u);
+            let k = (x + 0.5).floor().clamp(1.0, self.n);
+            if k - x <= self.threshold || u >= self.h_integral(k + 0.5) - self.h(k) {
+                return k as i64;
+            }
+        }
+    }
+}
+
+/// `(e^x - 1) / x`, continuous at zero.
+fn helper(x: f64) -> f64 {
+    if x.abs() > 1e-8 {
+        x.exp_m1() / x
+    } else {
+        1.0 + x / 2.0
+    }
+}
+
+/// `ln(1 + x) / x`, continuous at zero.
+fn helper_inv(x: f64) -> f64 {
+    if x.abs() > 1e-8 {
+        x.ln_1p() / x
+    } else {
+        1.0 - x / 2.0
+    }
+}
@@ -0,0 +1,415 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::sorter::Sorter;
+
+/// How integers are stored in the input and output files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Consecutive little-endian `i64`s.
+    Binary,
+    /// Whitespace-separated decimal integers; output has one per line.
+    Text,
+}
+
+/// At most this many runs are merged at once; more runs are merged in
+/// several passes so the number of open files stays bounded.
+pub const MAX_FAN_IN: usize = 256;
+
+const MIN_IO_BUFFER: usize = 64 * 1024;
+
+/// Longest text token accepted; any valid `i64` is at most 20 characters, so
+/// this only stops a runaway token from being buffered whole.
+const MAX_TOKEN: usize = 64;
+
+static RUN_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExternalStats {
+    pub elements: u64,
+    pub runs: usize,
+    pub merge_passes: usize,
+}
+
+/// Sorts files of `i64`s that may not fit in memory: chunks of at most
+/// `memory_budget` bytes are sorted in memory with `sorter` and spilled to
+/// temporary run files, which are then k-way merged through a heap.
+pub struct ExternalSort<'a> {
+    sorter: &'a dyn Sorter<i64>,
+    memory_budget: usize,
+    format: Format,
+    temp_dir: PathBuf,
+}
+
+impl<'a> ExternalSort<'a> {
+    pub fn new(sorter: &'a dyn Sorter<i64>) -> Self {
+        ExternalSort {
+            sorter,
+            memory_budget: 256 << 20,
+            format: Format::Binary,
+            temp_dir: std::env::temp_dir(),
+        }
+    }
+
+    /// Bytes of elements held in memory per chunk. Sorters that are not in
+    /// place need up to as much again for their buffer.
+    pub fn memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = dir.into();
+        self
+    }
+
+    pub fn sort_file(&self, input: impl AsRef<Path>, output: impl AsRef<Path>) -> io::Result<ExternalStats> {
+        let mut stats = ExternalStats::default();
+        let mut runs = RunFiles(Vec::new());
+        let chunk_len = (self.memory_budget / std::mem::size_of::<i64>()).max(1);
+
+        let mut reader = IntReader::new(File::open(input)?, self.format);
+        let mut chunk: Vec<i64> = Vec::with_capacity(chunk_len);
+        loop {
+            chunk.clear();
+            while chunk.len() < chunk_len {
+                match reader.next_value()? {
+                    Some(value) => chunk.push(value),
+                    None => break,
+                }
+            }
+            if chunk.is_empty() {
+                break;
+            }
+            stats.elements += chunk.len() as u64;
+            // A sorter that refuses the chunk, such as counting sort over a
+            // range too wide for its budget, fails the sort instead of panicking.
+            self.sorter.try_sort(&mut chunk).map_err(|e| io::Error::new(ErrorKind::InvalidInput, e.to_string()))?;
+            let path = self.new_run_path();
+            let mut writer = IntWriter::new(File::create(&path)?, Format::Binary, MIN_IO_BUFFER);
+            runs.0.push(path);
+            chunk.iter().try_for_each(|&value| writer.write_value(value))?;
+            writer.finish()?;
+        }
+        drop(chunk);
+        stats.runs = runs.0.len();
+
+        while runs.0.len() > MAX_FAN_IN {
+            let mut merged = RunFiles(Vec::new());
+            for group in runs.0.chunks(MAX_FAN_IN) {
+                let path = self.new_run_path();
+                merged.0.push(path.clone());
+                self.merge(group, &path, Format::Binary)?;
+            }
+            runs = merged;
+            stats.merge_passes += 1;
+        }
+        self.merge(&runs.0, output.as_ref(), self.format)?;
+        stats.merge_passes += 1;
+        Ok(stats)
+    }
+
+    fn merge(&self, runs: &[PathBuf], output: &Path, format: Format) -> io::Result<()> {
+        let buffer = (self.memory_budget / (runs.len() + 1)).max(MIN_IO_BUFFER);
+        let mut readers = runs
+            .iter()
+            .map(|path| Ok(IntReader::with_capacity(File::open(path)?, Format::Binary, buffer)))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut writer = IntWriter::new(File::create(output)?, format, buffer);
+
+        // The heap holds at most one value per run. Ties go to the earlier
+        // run, so equal values keep their input order.
+        let mut heap: BinaryHeap<Reverse<(i64, usize)>> = BinaryHeap::with_capacity(readers.len());
+        for (run, reader) in readers.iter_mut().enumerate() {
+            if let Some(value) = reader.next_value()? {
+                heap.push(Reverse((value, run)));
+            }
+        }
+        while let Some(Reverse((value, run))) = heap.pop() {
+            writer.write_value(value)?;
+            if let Some(next) = readers[run].next_value()? {
+                heap.push(Reverse((next, run)));
+            }
+        }
+        writer.finish()
+    }
+
+    fn new_run_path(&self) -> PathBuf {
+        let id = RUN_COUNTER.fetch_add(1, Ordering::Relaxed);
+        self.temp_dir.join(format!("sorting_algo_run_{}_{}.bin", std::process::id(), id))
+    }
+}
+
+/// Removes its run files when dropped, including on early error returns.
+struct RunFiles(Vec<PathBuf>);
+
+impl Drop for RunFiles {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+pub struct IntReader<R> {
+    reader: BufReader<R>,
+    format: Format,
+    token: Vec<u8>,
+}
+
+impl<R: Read> IntReader<R> {
+    pub fn new(inner: R, format: Format) -> Self {
+        Self::with_capacity(inner, format, MIN_IO_BUFFER)
+    }
+
+    pub fn with_capacity(inner: R, format: Format, capacity: usize) -> Self {
+        IntReader { reader: BufReader::with_capacity(capacity, inner), format, token: Vec::new() }
+    }
+
+    pub fn next_value(&mut self) -> io::Result<Option<i64>> {
+        match self.format {
+            Format::Binary => {
+                if self.reader.fill_buf()?.is_empty() {
+                    return Ok(None);
+                }
+                let mut bytes = [0u8; 8];
+                self.reader.read_exact(&mut bytes).map_err(|e| match e.kind() {
+                    io::ErrorKind::UnexpectedEof => {
+                        io::Error::new(io::ErrorKind::InvalidData, "file length is not a multiple of 8 bytes")
+                    }
+                    _ => e,
+                })?;
+                Ok(Some(i64::from_le_bytes(bytes)))
+            }
+            Format::Text => {
+                if !self.next_token()? {
+                    return Ok(None);
+                }
+                let token = String::from_utf8_lossy(&self.token);
+                token.parse().map(Some).map_err(|e| {
+                    io::Error::new(ErrorKind::InvalidData, format!("invalid integer '{}': {}", token, e))
+                })
+            }
+        }
+    }
+}
+
+impl<R: Read> IntReader<R> {
+    /// Reads the next whitespace-separated token into `self.token` straight
+    /// from the buffer, so memory stays bounded however long the lines are.
+    /// Returns false at the end of the input.
+    fn next_token(&mut self) -> io::Result<bool> {
+        self.token.clear();
+        loop {
+            let buffer = self.reader.fill_buf()?;
+            if buffer.is_empty() {
+                return Ok(!self.token.is_empty());
+            }
+            let skip = match self.token.is_empty() {
+                true => buffer.iter().take_while(|b| b.is_ascii_whitespace()).count(),
+                false => 0,
+            };
+            let length = buffer[skip..].iter().take_while(|b| !b.is_ascii_whitespace()).count();
+            self.token.extend_from_slice(&buffer[skip..skip + length]);
+            // Whitespace after the token in this buffer means it is complete;
+            // otherwise it may continue in the next fill.
+            let complete = skip + length < buffer.len();
+            self.reader.consume(skip + length);
+            if self.token.len() > MAX_TOKEN {
+                return Err(io::Error::new(ErrorKind::InvalidData, format!("token longer than {} bytes", MAX_TOKEN)));
+            }
+            if complete && !self.token.is_empty() {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+pub struct IntWriter<W: Write> {
+    writer: BufWriter<W>,
+    format: Format,
+}
+
+impl<W: Write> IntWriter<W> {
+    pub fn new(inner: W, format: Format, capacity: usize) -> Self {
+        IntWriter { writer: BufWriter::with_capacity(capacity, inner), format }
+    }
+
+    pub fn write_value(&mut self, value: i64) -> io::Result<()> {
+        match self.format {
+            Format::Binary => self.writer.write_all(&value.to_le_bytes()),
+            Format::Text => writeln!(self.writer, "{}", value),
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::counting::{CountingSort, DEFAULT_MEMORY_BUDGET};
+    use crate::merge::MergeSort;
+    use crate::quick::IntroSort;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// A fresh directory under the system temp dir, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("sorting_algo_test_{}_{}", name, std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write_values(path: &Path, values: &[i64], format: Format) {
+        let mut writer = IntWriter::new(File::create(path).unwrap(), format, MIN_IO_BUFFER);
+        values.iter().for_each(|&value| writer.write_value(value).unwrap());
+        writer.finish().unwrap();
+    }
+
+    fn read_values(path: &Path, format: Format) -> Vec<i64> {
+        let mut reader = IntReader::new(File::open(path).unwrap(), format);
+        std::iter::from_fn(|| reader.next_value().unwrap()).collect()
+    }
+
+    fn random_values(seed: u64, count: usize) -> Vec<i64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count).map(|_| rng.gen_range(-1000..1000)).collect()
+    }
+
+    #[test]
+    fn round_trip_through_several_runs() {
+        let dir = TempDir::new("round_trip");
+        let values = random_values(1, 1000);
+        let mut expected = values.clone();
+        expected.sort();
+        for format in [Format::Binary, Format::Text] {
+            let (input, output) = (dir.0.join("in"), dir.0.join("out"));
+            write_values(&input, &values, format);
+            // 100 values per chunk.
+            let sorter = MergeSort;
+            let stats = ExternalSort::new(&sorter)
+                .memory_budget(800)
+                .format(format)
+                .temp_dir(&dir.0)
+                .sort_file(&input, &output)
+                .unwrap();
+            assert_eq!(stats, ExternalStats { elements: 1000, runs: 10, merge_passes: 1 });
+            assert_eq!(read_values(&output, format), expected);
+        }
+        // Only the input and output are left; every run file was removed.
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn more_runs_than_fan_in_take_several_passes() {
+        let dir = TempDir::new("multi_pass");
+        let count = MAX_FAN_IN * 3 + 7;
+        let values = random_values(2, count);
+        let (input, output) = (dir.0.join("in"), dir.0.join("out"));
+        write_values(&input, &values, Format::Binary);
+        // One value per chunk, so every value is its own run.
+        let sorter = IntroSort;
+        let stats = ExternalSort::new(&sorter).memory_budget(8).temp_dir(&dir.0).sort_file(&input, &output).unwrap();
+        assert_eq!(stats.runs, count);
+        assert_eq!(stats.merge_passes, 2);
+        let mut expected = values;
+        expected.sort();
+        assert_eq!(read_values(&output, Format::Binary), expected);
+    }
+
+    #[test]
+    fn empty_input() {
+        let dir = TempDir::new("empty");
+        let (input, output) = (dir.0.join("in"), dir.0.join("out"));
+        write_values(&input, &[], Format::Text);
+        let sorter = IntroSort;
+        let stats = ExternalSort::new(&sorter).format(Format::Text).temp_dir(&dir.0).sort_file(&input, &output).unwrap();
+        assert_eq!(stats.elements, 0);
+        assert!(read_values(&output, Format::Text).is_empty());
+    }
+
+    #[test]
+    fn sorter_refusing_a_chunk_is_an_error() {
+        let dir = TempDir::new("refused");
+        let (input, output) = (dir.0.join("in"), dir.0.join("out"));
+        write_values(&input, &[i64::MIN, 0, i64::MAX], Format::Binary);
+        let sorter = CountingSort { memory_budget: DEFAULT_MEMORY_BUDGET };
+        let error = ExternalSort::new(&sorter).temp_dir(&dir.0).sort_file(&input, &output).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+        assert!(error.to_string().contains("key range"), "{}", error);
+    }
+
+    #[test]
+    fn text_tokens_span_buffer_refills() {
+        // A 1-byte buffer splits every token across refills.
+        let text = "12345 -6\n\n  789\t10\n-0";
+        let mut reader = IntReader::with_capacity(text.as_bytes(), Format::Text, 1);
+        let values: Vec<i64> = std::iter::from_fn(|| reader.next_value().unwrap()).collect();
+        assert_eq!(values, [12345, -6, 789, 10, 0]);
+
+        let mut reader = IntReader::new("1 2x 3".as_bytes(), Format::Text);
+        assert_eq!(reader.next_value().unwrap(), Some(1));
+        assert_eq!(reader.next_value().unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let long = "9".repeat(MAX_TOKEN * 4);
+        let mut reader = IntReader::with_capacity(long.as_bytes(), Format::Text, 8);
+        assert_eq!(reader.next_value().unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    /// Sorts a 2 GB file with a 64 MB budget. Run with
+    /// `cargo test --release -- --ignored external`.
+    #[test]
+    #[ignore]
+    fn multi_gigabyte_file() {
+        let dir = TempDir::new("multi_gb");
+        let (input, output) = (dir.0.join("in"), dir.0.join("out"));
+        let count = (2u64 << 30) / 8;
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut checksum = 0i64;
+        let mut writer = IntWriter::new(File::create(&input).unwrap(), Format::Binary, 1 << 20);
+        for _ in 0..count {
+            let value: i64 = rng.gen();
+            checksum = checksum.wrapping_add(value);
+            writer.write_value(value).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let sorter = IntroSort;
+        let stats = ExternalSort::new(&sorter).memory_budget(64 << 20).temp_dir(&dir.0).sort_file(&input, &output).unwrap();
+        assert_eq!(stats.elements, count);
+        assert_eq!(stats.runs, 32);
+
+        let mut reader = IntReader::with_capacity(File::open(&output).unwrap(), Format::Binary, 1 << 20);
+        let (mut seen, mut sorted_checksum, mut previous) = (0u64, 0i64, i64::MIN);
+        while let Some(value) = reader.next_value().unwrap() {
+            assert!(value >= previous, "output out of order after {} values", seen);
+            previous = value;
+            sorted_checksum = sorted_checksum.wrapping_add(value);
+            seen += 1;
+        }
+        assert_eq!(seen, count);
+        assert_eq!(sorted_checksum, checksum);
+    }
+}
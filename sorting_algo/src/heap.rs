@@ -0,0 +1,332 @@
+use std::cmp::Ordering;
+
+use crate::sorter::Sorter;
+
+// Slice-level d-ary heap primitives. They maintain a max-heap with respect to
+// `compare`; pass a reversed comparator for a min-heap.
+
+fn sift_up<T, F, const D: usize>(v: &mut [T], mut pos: usize, compare: &mut F, on_swap: &mut impl FnMut(usize, usize))
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while pos > 0 {
+        let parent = (pos - 1) / D;
+        if compare(&v[parent], &v[pos]) != Ordering::Less {
+            return;
+        }
+        v.swap(parent, pos);
+        on_swap(parent, pos);
+        pos = parent;
+    }
+}
+
+fn sift_down<T, F, const D: usize>(
+    v: &mut [T],
+    mut pos: usize,
+    end: usize,
+    compare: &mut F,
+    on_swap: &mut impl FnMut(usize, usize),
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    loop {
+        let first_child = D * pos + 1;
+        if first_child >= end {
+            return;
+        }
+        let mut largest = first_child;
+        for child in first_child + 1..(first_child + D).min(end) {
+            if compare(&v[largest], &v[child]) == Ordering::Less {
+                largest = child;
+            }
+        }
+        if compare(&v[pos], &v[largest]) != Ordering::Less {
+            return;
+        }
+        v.swap(pos, largest);
+        on_swap(pos, largest);
+        pos = largest;
+    }
+}
+
+/// Rearranges `v` into a d-ary max-heap in O(n).
+pub fn heapify_by<T, F, const D: usize>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    heapify_with::<T, F, D>(v, &mut compare, &mut |_, _| {});
+}
+
+fn heapify_with<T, F, const D: usize>(v: &mut [T], compare: &mut F, on_swap: &mut impl FnMut(usize, usize))
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    if len < 2 {
+        return;
+    }
+    for pos in (0..=(len - 2) / D).rev() {
+        sift_down::<T, F, D>(v, pos, len, compare, on_swap);
+    }
+}
+
+pub fn heapsort<T: Ord>(v: &mut [T]) {
+    heapsort_by::<T, _, 2>(v, T::cmp);
+}
+
+/// In-place heapsort: builds a d-ary max-heap, then repeatedly moves the root
+/// behind the shrinking heap.
+pub fn heapsort_by<T, F, const D: usize>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut no_op = |_, _| {};
+    heapify_with::<T, F, D>(v, &mut compare, &mut no_op);
+    for end in (1..v.len()).rev() {
+        v.swap(0, end);
+        sift_down::<T, F, D>(v, 0, end, &mut compare, &mut no_op);
+    }
+}
+
+/// Identifies an item pushed into a `DaryHeap`, stable across reorderings.
+/// Slots are reused after a `pop`, so each handle also carries the slot's
+/// generation; a handle whose item was popped never matches a later item.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    pub slot: usize,
+    pub generation: u64,
+}
+
+const REMOVED: usize = usize::MAX;
+
+/// Min-heap with `D` children per node that supports decrease-key through
+/// handles returned by `push`.
+pub struct DaryHeap<T, const D: usize> {
+    items: Vec<T>,
+    // handles[pos] is the handle stored at pos; positions[handle] is its pos.
+    handles: Vec<usize>,
+    positions: Vec<usize>,
+    // Bumped each time a slot is freed, invalidating handles to its old item.
+    generations: Vec<u64>,
+    // Handle slots freed by `pop`, so `positions` stays as large as the most
+    // items ever held at once rather than the number of pushes.
+    free: Vec<usize>,
+}
+
+pub type BinaryHeap<T> = DaryHeap<T, 2>;
+
+impl<T: Ord, const D: usize> DaryHeap<T, D> {
+    pub fn new() -> Self {
+        assert!(D >= 2, "a heap needs at least two children per node");
+        DaryHeap { items: Vec::new(), handles: Vec::new(), positions: Vec::new(), generations: Vec::new(), free: Vec::new() }
+    }
+
+    /// Builds a heap in O(n). The i-th item gets `Handle { slot: i, generation: 0 }`.
+    pub fn from_vec(items: Vec<T>) -> Self {
+        let mut heap = Self::new();
+        heap.handles = (0..items.len()).collect();
+        heap.positions = (0..items.len()).collect();
+        heap.generations = vec![0; items.len()];
+        heap.items = items;
+        let (items, handles, positions) = (&mut heap.items, &mut heap.handles, &mut heap.positions);
+        heapify_with::<T, _, D>(items, &mut |a: &T, b: &T| b.cmp(a), &mut |i, j| {
+            Self::swap_handles(handles, positions, i, j)
+        });
+        heap
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.position(handle).map(|pos| &self.items[pos])
+    }
+
+    /// Where the item behind `handle` is, if it is still in the heap.
+    fn position(&self, handle: Handle) -> Option<usize> {
+        match self.positions.get(handle.slot) {
+            Some(&pos) if pos != REMOVED && self.generations[handle.slot] == handle.generation => Some(pos),
+            _ => None,
+        }
+    }
+
+    pub fn push(&mut self, item: T) -> Handle {
+        let pos = self.items.len();
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.positions[slot] = pos;
+                slot
+            }
+            None => {
+                self.positions.push(pos);
+                self.generations.push(0);
+                self.positions.len() - 1
+            }
+        };
+        self.items.push(item);
+        self.handles.push(slot);
+        self.sift_up(pos);
+        Handle { slot, generation: self.generations[slot] }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.items.len().checked_sub(1)?;
+        self.swap(0, last);
+        let item = self.items.pop()?;
+        let slot = self.handles.pop()?;
+        self.positions[slot] = REMOVED;
+        self.generations[slot] += 1;
+        self.free.push(slot);
+        self.sift_down(0);
+        Some(item)
+    }
+
+    /// Replaces the item behind `handle` with a smaller or equal one.
+    ///
+    /// Panics if the handle was already popped or `item` is greater than the
+    /// current value.
+    pub fn decrease_key(&mut self, handle: Handle, item: T) {
+        let Some(pos) = self.position(handle) else {
+            panic!("decrease_key on a handle that is not in the heap");
+        };
+        assert!(item <= self.items[pos], "decrease_key called with a greater key");
+        self.items[pos] = item;
+        self.sift_up(pos);
+    }
+
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.items.swap(i, j);
+        Self::swap_handles(&mut self.handles, &mut self.positions, i, j);
+    }
+
+    fn swap_handles(handles: &mut [usize], positions: &mut [usize], i: usize, j: usize) {
+        handles.swap(i, j);
+        positions[handles[i]] = i;
+        positions[handles[j]] = j;
+    }
+
+    fn sift_up(&mut self, pos: usize) {
+        let (handles, positions) = (&mut self.handles, &mut self.positions);
+        sift_up::<T, _, D>(&mut self.items, pos, &mut |a: &T, b: &T| b.cmp(a), &mut |i, j| {
+            Self::swap_handles(handles, positions, i, j)
+        });
+    }
+
+    fn sift_down(&mut self, pos: usize) {
+        let end = self.items.len();
+        let (handles, positions) = (&mut self.handles, &mut self.positions);
+        sift_down::<T, _, D>(&mut self.items, pos, end, &mut |a: &T, b: &T| b.cmp(a), &mut |i, j| {
+            Self::swap_handles(handles, positions, i, j)
+        });
+    }
+}
+
+impl<T: Ord, const D: usize> Default for DaryHeap<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct HeapSort<const D: usize = 2>;
+
+impl<T: Ord, const D: usize> Sorter<T> for HeapSort<D> {
+    fn name(&self) -> &'static str {
+        match D {
+            2 => "Heapsort",
+            3 => "Heapsort (3-ary)",
+            4 => "Heapsort (4-ary)",
+            8 => "Heapsort (8-ary)",
+            _ => "Heapsort (d-ary)",
+        }
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        heapsort_by::<T, _, D>(data, T::cmp);
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn pops_in_ascending_order() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let data: Vec<i64> = (0..500).map(|_| rng.gen_range(-100..100)).collect();
+        let mut expected = data.clone();
+        expected.sort();
+        assert_eq!(DaryHeap::<_, 2>::from_vec(data.clone()).into_sorted_vec(), expected);
+        let mut heap = DaryHeap::<_, 4>::new();
+        for &x in &data {
+            heap.push(x);
+        }
+        assert_eq!(heap.into_sorted_vec(), expected);
+    }
+
+    #[test]
+    fn handle_slots_are_reused() {
+        let mut heap = BinaryHeap::new();
+        for i in 0..10_000 {
+            heap.push(i % 7);
+            if heap.len() > 4 {
+                heap.pop();
+            }
+        }
+        assert_eq!(heap.len(), 4);
+        assert!(heap.positions.len() <= 5, "{} handle slots for 4 items", heap.positions.len());
+    }
+
+    #[test]
+    fn decrease_key_after_reuse() {
+        let mut heap = BinaryHeap::new();
+        let a = heap.push(10);
+        heap.push(20);
+        assert_eq!(heap.pop(), Some(10));
+        assert_eq!(heap.get(a), None);
+        let b = heap.push(30);
+        let c = heap.push(40);
+        assert_eq!(b.slot, a.slot, "the popped slot is reused");
+        assert_eq!(heap.get(a), None);
+        heap.decrease_key(c, 5);
+        assert_eq!(heap.get(b), Some(&30));
+        assert_eq!(heap.get(a), None);
+        assert_eq!(heap.into_sorted_vec(), [5, 20, 30]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not in the heap")]
+    fn decrease_key_on_a_stale_handle_panics() {
+        let mut heap = BinaryHeap::new();
+        let stale = heap.push(10);
+        heap.pop();
+        heap.push(30);
+        heap.decrease_key(stale, 5);
+    }
+}
@@ -0,0 +1,122 @@
+use std::cmp::Ordering;
+
+use crate::sorter::Sorter;
+
+pub fn insertion_sort<T: Ord>(arr: &mut [T]) {
+    insertion_sort_by(arr, T::cmp);
+}
+
+pub fn insertion_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..arr.len() {
+        let mut j = i;
+        while j > 0 && compare(&arr[j], &arr[j - 1]) == Ordering::Less {
+            arr.swap(j, j - 1);
+            j -= 1;
+        }
+    }
+}
+
+pub fn insertion_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    insertion_sort_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn binary_insertion_sort<T: Ord>(arr: &mut [T]) {
+    binary_insertion_sort_by(arr, T::cmp);
+}
+
+/// Insertion sort that finds each insertion point by binary search, cutting
+/// comparisons to O(n log n) while the moves stay quadratic. Inserting after
+/// equal elements keeps it stable.
+pub fn binary_insertion_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..arr.len() {
+        let (sorted, rest) = arr.split_at(i);
+        let pos = sorted.partition_point(|x| compare(x, &rest[0]) != Ordering::Greater);
+        arr[pos..=i].rotate_right(1);
+    }
+}
+
+pub struct InsertionSort;
+
+impl<T: Ord> Sorter<T> for InsertionSort {
+    fn name(&self) -> &'static str {
+        "Insertion Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        insertion_sort(data);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+}
+
+pub struct BinaryInsertionSort;
+
+impl<T: Ord> Sorter<T> for BinaryInsertionSort {
+    fn name(&self) -> &'static str {
+        "Binary Insertion Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        binary_insertion_sort(data);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn binary_insertion_sort_sorts() {
+        let mut rng = StdRng::seed_from_u64(12);
+        let inputs: Vec<Vec<i32>> = vec![
+            vec![],
+            vec![1],
+            (0..500).map(|_| rng.gen_range(-100..100)).collect(),
+            (0..500).collect(),
+            (0..500).rev().collect(),
+            vec![7; 50],
+        ];
+        for mut data in inputs {
+            let mut expected = data.clone();
+            expected.sort();
+            binary_insertion_sort(&mut data);
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    fn binary_insertion_sort_is_stable() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let mut records: Vec<(u8, usize)> = (0..500).map(|i| (rng.gen_range(0..8), i)).collect();
+        let mut expected = records.clone();
+        expected.sort_by_key(|&(key, _)| key);
+        binary_insertion_sort_by(&mut records, |a, b| a.0.cmp(&b.0));
+        assert_eq!(records, expected);
+    }
+}
@@ -0,0 +1,19 @@
+pub mod bench;
+pub mod bucket;
+pub mod counting;
+pub mod cutoffs;
+pub mod distributions;
+pub mod external;
+pub mod heap;
+pub mod insertion;
+pub mod merge;
+pub mod parallel;
+pub mod quick;
+pub mod radix;
+pub mod select;
+pub mod selection;
+pub mod shell;
+pub mod sorter;
+pub mod timsort;
+
+pub use sorter::{Registry, Sorter};
@@ -4,187 +4,601 @@ use std::fs::File;
 use std::time::Instant;
 use std::io::{self, Write};
 use plotters::prelude::*;
+use sorting_algo::bench::{
+    self, input_rng, measure, plot_medians, plottable, screen_outliers, take_option, usage_error, warm_up, write_results, Config,
+    DoublingLimits, Point, Series, DEFAULT_BUDGET,
+};
+use sorting_algo::bucket::BucketSort;
+use sorting_algo::insertion::InsertionSort;
+use sorting_algo::merge::{merge_sort_buffered, BufferedMergeSort, MergeSort};
+use sorting_algo::parallel::{default_threads, parallel_merge_sort, parallel_radix_sort};
+use sorting_algo::radix::{counting_radix_sort, RadixSort};
+use sorting_algo::selection::SelectionSort;
+use sorting_algo::timsort::TimSort;
+use sorting_algo::cutoffs::{Cutoffs, CUTOFFS_FILE};
+use sorting_algo::distributions::Distribution;
+use sorting_algo::external::{ExternalSort, Format, IntReader, IntWriter};
+use sorting_algo::merge::hybrid_merge_sort;
+use sorting_algo::quick::{hybrid_quicksort, introsort, IntroSort};
+use sorting_algo::select::{median_of_medians_select, partial_sort, quickselect, top_k};
+use sorting_algo::sorter::{registry_with_cutoffs, Registry, Sorter};
 
+type SortFn = Box<dyn Fn(&mut [i64])>;
 
-fn bucket_sort(array: &mut [i64], k: usize) {
-    let mut buckets: Vec<Vec<i64>> = vec![Vec::new(); k];
-    let max_value = *array.iter().max().unwrap();
-    for &value in array.iter() {
-        let index = ((k as i64 * value) / (max_value + 1)) as usize;
-        buckets[index].push(value);
+fn plot_all_results(results: &[(String, Vec<(u128, f64)>)], filename: &str) {
+    let root = BitMapBackend::new(filename, (640, 480)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+
+    let (min_size, max_size) = (10_u128.pow(3) as i64, 3*10_u128.pow(8) as i64); 
+    let min_time = 0.0;
+    let max_time = results.iter()
+        .flat_map(|(_, times)| times.iter().map(|&(_, time)| time))
+        .fold(0.0, f64::max);
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Time Complexity Comparisons", ("sans-serif", 20))
+        .x_label_area_size(30)
+        .y_label_area_size(30)
+        .margin(5)
+        .build_cartesian_2d((min_size..max_size).log_scale(), (min_time..max_time).log_scale())
+        .unwrap();
+
+    chart.configure_mesh().x_desc("Size").y_desc("Time (s)").draw().unwrap();
+
+    for (i, (sorting, times)) in results.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba(); // Copy color for this iteration
+        chart.draw_series(LineSeries::new(
+            times.iter().map(|&(size, time)| (size as i64, time)),
+            &color,
+        )).unwrap()
+        .label(sorting)
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
     }
-    for bucket in &mut buckets {
-        selection_sort(bucket);
+
+    chart.configure_series_labels().border_style(BLACK).draw().unwrap();
+}
+
+fn plot_speedup(results: &[(String, Vec<(u128, f64)>)], filename: &str) {
+    let root = BitMapBackend::new(filename, (640, 480)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+
+    let min_size = results.iter().flat_map(|(_, points)| points.iter().map(|&(size, _)| size as i64)).min().unwrap_or(1);
+    let max_size = results.iter().flat_map(|(_, points)| points.iter().map(|&(size, _)| size as i64)).max().unwrap_or(2);
+    let max_speedup = results.iter()
+        .flat_map(|(_, points)| points.iter().map(|&(_, speedup)| speedup))
+        .fold(1.0, f64::max);
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Parallel Speedup over Sequential", ("sans-serif", 20))
+        .x_label_area_size(30)
+        .y_label_area_size(30)
+        .margin(5)
+        .build_cartesian_2d((min_size..max_size).log_scale(), 0.0..max_speedup * 1.1)
+        .unwrap();
+
+    chart.configure_mesh().x_desc("Size").y_desc("Speedup").draw().unwrap();
+
+    for (i, (label, points)) in results.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        chart.draw_series(LineSeries::new(
+            points.iter().map(|&(size, speedup)| (size as i64, speedup)),
+            &color,
+        )).unwrap()
+        .label(label)
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
     }
-    let mut idx = 0;
-    for bucket in buckets {
-        for &value in bucket.iter() {
-            array[idx] = value;
-            idx += 1;
+
+    chart.configure_series_labels().border_style(BLACK).draw().unwrap();
+}
+
+// Times the parallel sorts at 1, 2, 4, ... threads against their sequential
+// counterparts and plots the speedup per thread count.
+fn parallel_speedup(seed: u64) -> io::Result<()> {
+    let sizes = [100000, 1000000, 10000000, 50000000];
+    let max_threads = default_threads();
+    let mut thread_counts: Vec<usize> = std::iter::successors(Some(1), |&t| Some(t * 2))
+        .take_while(|&t| t < max_threads)
+        .collect();
+    thread_counts.push(max_threads);
+
+    let mut results: Vec<(String, Vec<(u128, f64)>)> = Vec::new();
+    for algorithm in ["Merge Sort", "Radix Sort"] {
+        for &threads in &thread_counts {
+            results.push((format!("{}, {} threads", algorithm, threads), Vec::new()));
         }
     }
-}
 
+    let mut file = File::create("parallel_speedup.txt")?;
+    writeln!(file, "Seed: {}", seed)?;
+    for &size in sizes.iter() {
+        let arr = Distribution::Uniform.generate(size, &mut input_rng(seed, size, 0));
 
-fn radix_sort_base10(nums: &mut [i64]) {
-    let mut buckets = vec![vec![]; 10];
-    for i in 0..10 {
-        nums.iter()
-            .for_each(|&x| buckets[((x / 10_i64.pow(i)) % 10) as usize].push(x));
-        buckets
-            .iter()
-            .flat_map(|b| b.iter())
-            .zip(nums.iter_mut())
-            .for_each(|(&x, y)| *y = x);
-        buckets.iter_mut().for_each(|b| b.clear());
+        let time = |sort: &dyn Fn(&mut [i64])| {
+            let mut data = arr.clone();
+            let start = Instant::now();
+            sort(&mut data);
+            start.elapsed().as_secs_f64()
+        };
+        let sequential = [
+            time(&|data| merge_sort_buffered(data)),
+            time(&|data| counting_radix_sort(data, 256)),
+        ];
+        writeln!(file, "Size: {}, Merge Sort: {}, Radix Sort: {}", size, sequential[0], sequential[1])?;
+
+        for (t, &threads) in thread_counts.iter().enumerate() {
+            let parallel = [
+                time(&|data| parallel_merge_sort(data, threads)),
+                time(&|data| parallel_radix_sort(data, 256, threads)),
+            ];
+            for (a, (&seq, &par)) in sequential.iter().zip(parallel.iter()).enumerate() {
+                results[a * thread_counts.len() + t].1.push((size as u128, seq / par));
+            }
+            writeln!(file, "Size: {}, Threads: {}, Parallel Merge Sort: {}, Parallel Radix Sort: {}",
+                size, threads, parallel[0], parallel[1])?;
+            println!("Parallel sorts of size {} on {} threads: merge {:.2}x, radix {:.2}x",
+                size, threads, sequential[0] / parallel[0], sequential[1] / parallel[1]);
+        }
     }
+
+    plot_speedup(&results, "speedup.png");
+    Ok(())
+}
+
+fn plot_bucket_heatmap(sizes: &[usize], ks: &[usize], times: &[Vec<Option<f64>>], filename: &str) {
+    let root = BitMapBackend::new(filename, (800, 600)).into_drawing_area();
+    root.fill(&WHITE).unwrap();
+
+    let measured = times.iter().flatten().flatten().map(|t| t.ln());
+    let min_log = measured.clone().fold(f64::INFINITY, f64::min);
+    let max_log = measured.fold(f64::NEG_INFINITY, f64::max);
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Bucket Sort Time over (n, k)", ("sans-serif", 20))
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .margin(5)
+        .build_cartesian_2d(
+            (0..sizes.len().saturating_sub(1)).into_segmented(),
+            (0..ks.len().saturating_sub(1)).into_segmented(),
+        )
+        .unwrap();
+
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .x_desc("Size")
+        .y_desc("Buckets (k)")
+        .x_labels(sizes.len())
+        .y_labels(ks.len())
+        .x_label_formatter(&|v| match v {
+            SegmentValue::CenterOf(i) => sizes.get(*i).map(|s| s.to_string()).unwrap_or_default(),
+            _ => String::new(),
+        })
+        .y_label_formatter(&|v| match v {
+            SegmentValue::CenterOf(j) => ks.get(*j).map(|k| k.to_string()).unwrap_or_default(),
+            _ => String::new(),
+        })
+        .draw()
+        .unwrap();
+
+    // Cells go from green (fastest) to red (slowest) on a log scale; skipped cells stay grey.
+    chart.draw_series(times.iter().enumerate().flat_map(|(i, row)| {
+        row.iter().enumerate().map(move |(j, time)| {
+            let color = match time {
+                Some(t) if max_log > min_log => {
+                    let frac = (t.ln() - min_log) / (max_log - min_log);
+                    HSLColor((1.0 - frac) / 3.0, 0.8, 0.5).to_rgba()
+                }
+                Some(_) => HSLColor(1.0 / 3.0, 0.8, 0.5).to_rgba(),
+                None => RGBColor(200, 200, 200).to_rgba(),
+            };
+            let from = (SegmentValue::Exact(i), SegmentValue::Exact(j));
+            let to = (SegmentValue::Exact(i + 1), SegmentValue::Exact(j + 1));
+            Rectangle::new([from, to], color.filled())
+        })
+    })).unwrap();
 }
 
-fn selection_sort(arr: &mut Vec<i64>) {
-    let len = arr.len();
-    for i in 0..len {
-        let mut min_idx = i;
-        for j in (i + 1)..len {
-            if arr[j] < arr[min_idx] {
-                min_idx = j;
+// Sweeps the bucket count for every size and renders the timings as a heatmap,
+// so `k` can be picked empirically for a given inner sort.
+fn bucket_sweep(inner_name: &str, seed: u64) -> io::Result<()> {
+    let sizes = [1000, 5000, 10000, 50000, 100000, 500000, 1000000, 5000000];
+    let ks = [1, 4, 16, 64, 256, 1024, 4096, 16384, 65536];
+    // Once a k has taken this long, larger sizes with the same k are skipped.
+    let cell_limit = 10.0;
+
+    let mut inners: Registry<i64> = Registry::new();
+    inners
+        .register(SelectionSort)
+        .register(InsertionSort)
+        .register(MergeSort)
+        .register(RadixSort { radix: 10 });
+    let inner = match inners.lookup(inner_name) {
+        Some(inner) => inner,
+        None => {
+            let names: Vec<&str> = inners.iter().map(|s| s.name()).collect();
+            eprintln!("Unknown inner sort '{}', expected one of: {}", inner_name, names.join(", "));
+            std::process::exit(2);
+        }
+    };
+
+    let mut times: Vec<Vec<Option<f64>>> = vec![vec![None; ks.len()]; sizes.len()];
+    let mut file = File::create("bucket_sort_times.txt")?;
+    writeln!(file, "Seed: {}", seed)?;
+    writeln!(file, "Algorithm: Bucket Sort, Inner: {}", inner.name())?;
+    for (i, &size) in sizes.iter().enumerate() {
+        let arr = Distribution::Uniform.generate(size, &mut input_rng(seed, size, 0));
+        for (j, &k) in ks.iter().enumerate() {
+            if i > 0 && times[i - 1][j].is_none_or(|t| t > cell_limit) {
+                continue;
             }
+            let mut data = arr.clone();
+            let sorter = BucketSort { buckets: k, inner };
+            let start = Instant::now();
+            sorter.sort(&mut data);
+            let duration = start.elapsed().as_secs_f64();
+            times[i][j] = Some(duration);
+            writeln!(file, "Size: {}, K: {}, Time: {}", size, k, duration)?;
+            println!("Bucket sorted array of size {} with k = {} in {} seconds", size, k, duration);
         }
-        arr.swap(i, min_idx); 
     }
+
+    plot_bucket_heatmap(&sizes, &ks, &times, "bucket_heatmap.png");
+    Ok(())
+}
+
+// Compares the adaptive sort with the plain merge sorts on sorted input that
+// had 1% of its positions randomly swapped.
+fn nearly_sorted(config: &Config) -> io::Result<()> {
+    let sizes = [1000, 10000, 100000, 1000000, 10000000, 50000000];
+
+    let mut sorters: Registry<i64> = Registry::new();
+    sorters.register(MergeSort).register(BufferedMergeSort).register(TimSort);
+    let mut results: Vec<Series> = sorters.iter().map(|sorter| Series::new(sorter.name())).collect();
+
+    for &size in sizes.iter() {
+        let distribution = Distribution::NearlySorted { swaps: size / 100 };
+        measure_size(&sorters.iter().collect::<Vec<_>>(), &mut results, distribution, size, config);
+    }
+
+    write_results("nearly_sorted_times.txt", config.seed, &results)?;
+    plot_medians(&plottable(&results), "nearly_sorted.png");
+    Ok(())
 }
 
-fn merge(left: &Vec<i64>, right: &Vec<i64>) -> Vec<i64> {
-    let mut i = 0;
-    let mut j = 0;
-    let mut merged: Vec<i64> = Vec::new();
+// Times the hybrid sorts over a range of insertion-sort cutoffs on this machine
+// and saves the fastest ones for later benchmark runs.
+fn tune_cutoffs(seed: u64) -> io::Result<()> {
+    let size = 1000000;
+    let trials = 5;
+    let candidates = [1, 4, 8, 12, 16, 24, 32, 48, 64, 96, 128];
+
+    let arr = Distribution::Uniform.generate(size, &mut input_rng(seed, size, 0));
+    // Best of several trials, so a single scheduling hiccup does not pick the cutoff.
+    let best_time = |sort: &dyn Fn(&mut [i64])| {
+        (0..trials)
+            .map(|_| {
+                let mut data = arr.clone();
+                let start = Instant::now();
+                sort(&mut data);
+                start.elapsed().as_secs_f64()
+            })
+            .fold(f64::INFINITY, f64::min)
+    };
 
-    while i < left.len() && j < right.len() {
-        if left[i] < right[j] {
-            merged.push(left[i]);
-            i = i + 1;
-        } else {
-            merged.push(right[j]);
-            j = j + 1;
+    let mut cutoffs = Cutoffs::default();
+    let (mut best_merge, mut best_quick) = (f64::INFINITY, f64::INFINITY);
+    for &cutoff in candidates.iter() {
+        let merge_time = best_time(&|data| hybrid_merge_sort(data, cutoff));
+        let quick_time = best_time(&|data| hybrid_quicksort(data, cutoff));
+        println!("Cutoff {}: merge {} seconds, quick {} seconds", cutoff, merge_time, quick_time);
+        if merge_time < best_merge {
+            best_merge = merge_time;
+            cutoffs.merge = cutoff;
+        }
+        if quick_time < best_quick {
+            best_quick = quick_time;
+            cutoffs.quick = cutoff;
         }
     }
 
-    if i < left.len() {
-        while i < left.len() {
-            merged.push(left[i]);
-            i = i + 1;
+    cutoffs.save(CUTOFFS_FILE)?;
+    println!("Saved merge cutoff {} and quick cutoff {} to {}", cutoffs.merge, cutoffs.quick, CUTOFFS_FILE);
+    Ok(())
+}
+
+// Finding the median or the top 100 directly versus fully sorting first.
+fn selection_benchmark(seed: u64) -> io::Result<()> {
+    let sizes = [1000, 10000, 100000, 1000000, 10000000, 50000000];
+    let k = 100;
+
+    let methods: Vec<(&str, SortFn)> = vec![
+        ("Quickselect (median)", Box::new(|data: &mut [i64]| {
+            let mid = data.len() / 2;
+            quickselect(data, mid);
+        })),
+        ("Median of Medians (median)", Box::new(|data: &mut [i64]| {
+            let mid = data.len() / 2;
+            median_of_medians_select(data, mid);
+        })),
+        ("Introsort then index (median)", Box::new(|data: &mut [i64]| introsort(data))),
+        ("Partial Sort (k = 100)", Box::new(move |data: &mut [i64]| partial_sort(data, k))),
+        ("Top-k Heap (k = 100)", Box::new(move |data: &mut [i64]| {
+            top_k(data.iter().copied(), k);
+        })),
+        ("Counting Radix Sort then slice (k = 100)", Box::new(|data: &mut [i64]| counting_radix_sort(data, 256))),
+    ];
+    let mut results: Vec<(String, Vec<(u128, f64)>)> = methods
+        .iter()
+        .map(|(name, _)| (name.to_string(), Vec::new()))
+        .collect();
+
+    for &size in sizes.iter() {
+        let arr = Distribution::Uniform.generate(size, &mut input_rng(seed, size, 0));
+        for ((name, method), (_, times)) in methods.iter().zip(results.iter_mut()) {
+            let mut data = arr.clone();
+            let start = Instant::now();
+            method(&mut data);
+            let duration = start.elapsed().as_secs_f64();
+            times.push((size as u128, duration));
+            println!("{} on array of size {} in {} seconds", name, size, duration);
         }
     }
 
-    if j < right.len() {
-        while j < right.len() {
-            merged.push(right[j]);
-            j = j + 1;
+    let mut file = File::create("select_times.txt")?;
+    writeln!(file, "Seed: {}", seed)?;
+    for (algorithm, times) in results.iter() {
+        writeln!(file, "Algorithm: {}", algorithm)?;
+        for (size, time) in times {
+            writeln!(file, "Size: {}, Time: {}", size, time)?;
         }
     }
 
-    merged
+    plot_all_results(&results, "select.png");
+    Ok(())
 }
 
-fn merge_sort(vec: &Vec<i64>) -> Vec<i64> {
-    if vec.len() < 2 {
-        vec.to_vec()
-    } else {
-        let size = vec.len() / 2;
-        let left = merge_sort(&vec[0..size].to_vec());
-        let right = merge_sort(&vec[size..].to_vec());
-        let merged = merge(&left, &right);
-
-        merged
+// external <input> <output> [--text] [--memory MB] [--algo NAME]
+fn external_sort(args: &[String]) -> io::Result<()> {
+    let (mut paths, mut format, mut memory_mb, mut algo) = (Vec::new(), Format::Binary, 256, "Introsort".to_string());
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--text" => format = Format::Text,
+            "--memory" => {
+                memory_mb = iter.next().and_then(|v| v.parse().ok())
+                    .unwrap_or_else(|| usage_error("--memory expects a size in megabytes"));
+            }
+            "--algo" => algo = iter.next().cloned().unwrap_or_else(|| usage_error("--algo expects a sorter name")),
+            _ => paths.push(arg),
+        }
+    }
+    if paths.len() != 2 {
+        usage_error("usage: external <input> <output> [--text] [--memory MB] [--algo NAME]");
     }
-}
 
+    let registry: Registry<i64> = registry_with_cutoffs(&Cutoffs::load_or_default(CUTOFFS_FILE));
+    let sorter = registry.lookup(&algo).unwrap_or_else(|| usage_error(&format!("unknown sorter '{}'", algo)));
+    let start = Instant::now();
+    let stats = ExternalSort::new(sorter)
+        .memory_budget(memory_mb << 20)
+        .format(format)
+        .sort_file(paths[0], paths[1])
+        .unwrap_or_else(|e| {
+            eprintln!("external sort failed: {}", e);
+            std::process::exit(1);
+        });
+    println!("Sorted {} integers in {} runs and {} merge passes in {} seconds",
+        stats.elements, stats.runs, stats.merge_passes, start.elapsed().as_secs_f64());
+    Ok(())
+}
 
-fn plot_all_results(results: &[(String, Vec<(u128, f64)>)]) {
-    let root = BitMapBackend::new("plot.png", (640, 480)).into_drawing_area();
-    root.fill(&WHITE).unwrap();
+// external-test [GB] [memory MB]: generates a binary file of random integers in
+// the temp directory, sorts it externally and checks the result streams back
+// sorted with the same count and checksum.
+fn external_test(args: &[String], seed: u64) -> io::Result<()> {
+    let gigabytes: f64 = args.first().and_then(|v| v.parse().ok()).unwrap_or(2.0);
+    let memory_mb: usize = args.get(1).and_then(|v| v.parse().ok()).unwrap_or(256);
+    let count = (gigabytes * (1u64 << 30) as f64) as u64 / 8;
 
-    let (min_size, max_size) = (10_u128.pow(3) as i64, 3*10_u128.pow(8) as i64); 
-    let min_time = 0.0;
-    let max_time = results.iter()
-        .flat_map(|(_, times)| times.iter().map(|&(_, time)| time))
-        .fold(0.0, f64::max);
+    let dir = std::env::temp_dir();
+    let input = dir.join(format!("sorting_algo_external_in_{}.bin", std::process::id()));
+    let output = dir.join(format!("sorting_algo_external_out_{}.bin", std::process::id()));
 
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Time Complexity Comparisons", ("sans-serif", 20))
-        .x_label_area_size(30)
-        .y_label_area_size(30)
-        .margin(5)
-        .build_cartesian_2d((min_size..max_size).log_scale(), (min_time..max_time).log_scale())
-        .unwrap();
+    let mut rng = input_rng(seed, count as usize, 0);
+    let mut checksum: i64 = 0;
+    let mut writer = IntWriter::new(File::create(&input)?, Format::Binary, 1 << 20);
+    for _ in 0..count {
+        let value: i64 = rng.gen();
+        checksum = checksum.wrapping_add(value);
+        writer.write_value(value)?;
+    }
+    writer.finish()?;
+    println!("Generated {} integers ({} GB) in {}", count, gigabytes, input.display());
 
-    chart.configure_mesh().x_desc("Size").y_desc("Time (s)").draw().unwrap();
+    let sorter = IntroSort;
+    let start = Instant::now();
+    let stats = ExternalSort::new(&sorter).memory_budget(memory_mb << 20).sort_file(&input, &output)?;
+    let duration = start.elapsed().as_secs_f64();
 
-    let colors = vec![RED, GREEN, BLUE];
-    for (i, (sorting, times)) in results.iter().enumerate() {
-        let color = colors[i]; // Copy color for this iteration
-        chart.draw_series(LineSeries::new(
-            times.iter().map(|&(size, time)| (size as i64, time)),
-            &color,
-        )).unwrap()
-        .label(sorting)
-        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &color));
+    let mut reader = IntReader::with_capacity(File::open(&output)?, Format::Binary, 1 << 20);
+    let (mut seen, mut sorted_checksum, mut previous, mut in_order) = (0u64, 0i64, i64::MIN, true);
+    while let Some(value) = reader.next_value()? {
+        in_order &= value >= previous;
+        previous = value;
+        sorted_checksum = sorted_checksum.wrapping_add(value);
+        seen += 1;
     }
+    std::fs::remove_file(&input)?;
+    std::fs::remove_file(&output)?;
 
-    chart.configure_series_labels().border_style(&BLACK).draw().unwrap();
+    let passed = in_order && seen == count && sorted_checksum == checksum;
+    println!("External sort of {} integers with {} MB: {} runs, {} merge passes, {} seconds, {}",
+        count, memory_mb, stats.runs, stats.merge_passes, duration, if passed { "PASS" } else { "FAIL" });
+    if !passed {
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
 fn main() -> io::Result<()> {
+    let mut args: Vec<String> = std::env::args().collect();
+    let mut config = Config::take_from(&mut args);
+    config.budget = take_option(&mut args, "--budget", "a positive number of seconds").unwrap_or(DEFAULT_BUDGET);
+    if config.budget.is_nan() || config.budget <= 0.0 {
+        usage_error("--budget expects a positive number of seconds");
+    }
+    let seed = config.seed;
+    println!("Seed: {}", seed);
+    match args.get(1).map(String::as_str) {
+        Some("bucket-sweep") => bucket_sweep(args.get(2).map_or("Selection", String::as_str), seed),
+        Some("parallel-speedup") => parallel_speedup(seed),
+        Some("nearly-sorted") => nearly_sorted(&config),
+        Some("tune-cutoffs") => tune_cutoffs(seed),
+        Some("select") => selection_benchmark(seed),
+        Some("distributions") => distribution_benchmark(&args[2..], &config),
+        Some("doubling") => doubling(args[2..].to_vec(), &config),
+        Some("external") => external_sort(&args[2..]),
+        Some("external-test") => external_test(&args[2..], seed),
+        None => run_benchmark(&config),
+        Some(other) => usage_error(&format!(
+            "unknown subcommand '{}'; expected one of: bucket-sweep, parallel-speedup, nearly-sorted, \
+             tune-cutoffs, select, distributions, doubling, external, external-test",
+            other
+        )),
+    }
+}
+
+fn run_benchmark(config: &Config) -> io::Result<()> {
+    benchmark_distribution(Distribution::Uniform, config, "radix_sort_times.txt", "plot.png")
+}
+
+/// Runs the full registry on inputs of each named distribution, writing
+/// `<name>_times.txt` and `<name>.png` per distribution; no names means all of them.
+fn distribution_benchmark(args: &[String], config: &Config) -> io::Result<()> {
+    let distributions = if args.is_empty() {
+        Distribution::all()
+    } else {
+        args.iter()
+            .map(|spec| Distribution::parse(spec).unwrap_or_else(|e| usage_error(&e)))
+            .collect()
+    };
+    for distribution in distributions {
+        println!("Distribution: {}", distribution);
+        let slug = distribution.slug();
+        benchmark_distribution(distribution, config, &format!("{}_times.txt", slug), &format!("{}.png", slug))?;
+    }
+    Ok(())
+}
+
+fn benchmark_distribution(distribution: Distribution, config: &Config, results_file: &str, plot_file: &str) -> io::Result<()> {
     let sizes_bucket = [1000, 
     5000, 10000, 50000, 100000, 500000, 
     1000000, 5000000, 10000000, 50000000];
 
-    let mut results: Vec<(String, Vec<(u128, f64)>)> = Vec::new();
-    let mut radix_sort_times: Vec<(u128, f64)> = Vec::new();
-    let mut selection_sort_times: Vec<(u128, f64)> = Vec::new();
-    let mut merge_sort_times: Vec<(u128, f64)> = Vec::new();
+    let registry: Registry<i64> = registry_with_cutoffs(&Cutoffs::load_or_default(CUTOFFS_FILE));
+    let mut results: Vec<Series> = registry.iter().map(|sorter| Series::new(sorter.name())).collect();
     
     for &size in sizes_bucket.iter() {
-        let mut rng = rand::thread_rng();
-        let mut arr: Vec<i64> = (0..size).map(|_| rng.gen_range(0..=size as i64)).collect();
-        
-        let start = Instant::now();
-        
-        radix_sort_base10(&mut arr);
-        
-        let duration = start.elapsed().as_secs_f64();
-        
-        radix_sort_times.push((size as u128, duration));
-        
-        println!("Radix sorted array of size {} in {} seconds", size, duration);
-
-        if size <= 1_00000 {
-            let start = Instant::now();
-            selection_sort(&mut arr);
-            let duration = start.elapsed().as_secs_f64();
-            selection_sort_times.push((size as u128, duration));
-            println!("Selection sorted array of size {} in {} seconds", size, duration);
-        }
-        if size <= 5_0000000 {
-            let start = Instant::now();
-            merge_sort(&mut arr);
-            let duration = start.elapsed().as_secs_f64();
-            merge_sort_times.push((size as u128, duration));
-            println!("Merge sorted array of size {} in {} seconds", size, duration);
-        }
+        measure_size(&registry.iter().collect::<Vec<_>>(), &mut results, distribution, size, config);
     }
     
-    results.push(("Radix Sort".to_string(), radix_sort_times));
-    results.push(("Selection Sort".to_string(), selection_sort_times));
-    results.push(("Merge Sort".to_string(), merge_sort_times));
-    
-    let mut file = File::create("radix_sort_times.txt")?;
-    for (algorithm, times) in results.iter() {
-        writeln!(file, "Algorithm: {}", algorithm)?;
-        for (size, time) in times {
-            writeln!(file, "Size: {}, Time: {}", size, time)?;
+    write_results(results_file, config.seed, &results)?;
+    plot_medians(&plottable(&results), plot_file);
+    Ok(())
+}
+
+// doubling <sorter> [--dist SPEC] [--start N] [--max-time SECONDS] [--max-memory MB]:
+// runs bench::doubling on one sorter and writes every step's trials to
+// doubling_times.txt.
+fn doubling(mut args: Vec<String>, config: &Config) -> io::Result<()> {
+    let distribution = take_option::<String>(&mut args, "--dist", "a distribution")
+        .map_or(Distribution::Uniform, |spec| Distribution::parse(&spec).unwrap_or_else(|e| usage_error(&e)));
+    let limits = DoublingLimits::take_from(&mut args);
+    let [name] = args.as_slice() else {
+        usage_error(&format!("usage: doubling <sorter> [--dist SPEC] {}", DoublingLimits::USAGE));
+    };
+
+    let registry: Registry<i64> = registry_with_cutoffs(&Cutoffs::load_or_default(CUTOFFS_FILE));
+    let sorter = registry.lookup(name).unwrap_or_else(|| usage_error(&format!("unknown sorter '{}'", name)));
+    let mut results = vec![Series::new(sorter.name())];
+    // The doubling limits decide when to stop, not the run budget.
+    let step_config = Config { budget: f64::INFINITY, ..*config };
+    // Input, reference copy, working copy and room for one scratch buffer.
+    let footprint = |n: usize| 4 * n * std::mem::size_of::<i64>();
+    bench::doubling(sorter.name(), &limits, footprint, |size| {
+        measure_size(&[sorter], &mut results, distribution, size, &step_config);
+        // Nothing to summarise when the sorter refused or failed the size.
+        results[0].summaries().last().filter(|(n, _)| *n == size as u128).map(|&(_, summary)| summary)
+    });
+    write_results("doubling_times.txt", config.seed, &results)
+}
+
+// Times every sorter at one size. Each (size, trial) gets one input and every
+// algorithm sorts its own copy of it; afterwards each algorithm's trials are
+// screened for outliers, regenerating an input from its trial number when it
+// has to be re-timed. Algorithms predicted to overrun their time budget skip
+// the size, as do ones that refuse its input.
+fn measure_size(sorters: &[&dyn Sorter<i64>], results: &mut [Series], distribution: Distribution, size: usize, config: &Config) {
+    let input = |trial| {
+        let arr = distribution.generate(size, &mut input_rng(config.seed, size, trial));
+        let mut expected = arr.clone();
+        expected.sort_unstable();
+        (arr, expected)
+    };
+
+    let runs = config.trials + config.warmup;
+    let mut active: Vec<bool> = results
+        .iter_mut()
+        .map(|series| match series.check_budget(size, runs, config.budget) {
+            Ok(()) => true,
+            Err(reason) => {
+                println!("{}: skipping size {}: {}", series.name, size, reason);
+                series.skipped.push((size, reason));
+                false
+            }
+        })
+        .collect();
+
+    for trial in 0..config.trials {
+        let (arr, expected) = input(trial);
+        for ((sorter, series), active) in sorters.iter().zip(results.iter_mut()).zip(active.iter_mut()) {
+            if !*active {
+                continue;
+            }
+            // Sorters that refuse an input, such as pigeonhole sort over its
+            // memory budget, skip the size instead of ending the run.
+            let outcome = if trial == 0 { warm_up(sorter, &arr, config.warmup) } else { Ok(0.0) }
+                .and_then(|warm| Ok((warm, measure(sorter, &arr, &expected)?)));
+            match outcome {
+                Ok((warm, (duration, check))) => {
+                    series.spent += warm + duration;
+                    series.points.push(Point::new(size, trial, duration, check));
+                    println!("{} sorted {} array {}#{} in {} seconds: {}", sorter.name(), distribution, size, trial, duration, check);
+                }
+                Err(e) => {
+                    let measured = series.trials_mut(size).len();
+                    series.points.truncate(series.points.len() - measured);
+                    println!("{}: skipping size {}: sorter refused input: {}", sorter.name(), size, e);
+                    series.skipped.push((size, format!("sorter refused input: {}", e)));
+                    *active = false;
+                }
+            }
+        }
+    }
+
+    for ((sorter, series), _) in sorters.iter().zip(results.iter_mut()).zip(&active).filter(|(_, &active)| active) {
+        let mut remeasured = 0.0;
+        let discarded = screen_outliers(series.trials_mut(size), config, |trial| {
+            let (arr, expected) = input(trial);
+            // The sorter already accepted this exact input once.
+            let (duration, check) = measure(sorter, &arr, &expected).expect("sorter refused an input it had sorted");
+            remeasured += duration;
+            (duration, check)
+        });
+        series.spent += remeasured;
+        if discarded > 0 {
+            println!("{}: discarded {} outlying trial(s) of size {}", sorter.name(), discarded, size);
         }
     }
-    
-    plot_all_results(&results);
-    Ok(())
 }
@@ -0,0 +1,360 @@
+use std::cmp::Ordering;
+
+use crate::insertion::insertion_sort_by;
+use crate::sorter::Sorter;
+
+pub fn merge<T: Ord + Clone>(left: &[T], right: &[T]) -> Vec<T> {
+    merge_by(left, right, &mut T::cmp)
+}
+
+/// Merges two sorted runs; on ties the element from `left` comes first, which
+/// keeps `merge_sort` stable.
+pub fn merge_by<T, F>(left: &[T], right: &[T], compare: &mut F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut i = 0;
+    let mut j = 0;
+    let mut merged: Vec<T> = Vec::with_capacity(left.len() + right.len());
+
+    while i < left.len() && j < right.len() {
+        if compare(&right[j], &left[i]) == Ordering::Less {
+            merged.push(right[j].clone());
+            j += 1;
+        } else {
+            merged.push(left[i].clone());
+            i += 1;
+        }
+    }
+
+    merged.extend_from_slice(&left[i..]);
+    merged.extend_from_slice(&right[j..]);
+
+    merged
+}
+
+pub fn merge_sort<T: Ord + Clone>(vec: &[T]) -> Vec<T> {
+    merge_sort_by(vec, T::cmp)
+}
+
+pub fn merge_sort_by<T, F>(vec: &[T], mut compare: F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_rec(vec, &mut compare)
+}
+
+pub fn merge_sort_by_key<T, K, F>(vec: &[T], mut f: F) -> Vec<T>
+where
+    T: Clone,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_by(vec, |a, b| f(a).cmp(&f(b)))
+}
+
+fn merge_sort_rec<T, F>(vec: &[T], compare: &mut F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if vec.len() < 2 {
+        vec.to_vec()
+    } else {
+        let size = vec.len() / 2;
+        let left = merge_sort_rec(&vec[0..size], compare);
+        let right = merge_sort_rec(&vec[size..], compare);
+        merge_by(&left, &right, compare)
+    }
+}
+
+/// Merges the sorted runs `left` and `right` into `out`, which must be exactly
+/// as long as both together. Ties take from `left`.
+pub(crate) fn merge_into<T, F>(left: &[T], right: &[T], out: &mut [T], compare: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let (mut i, mut j) = (0, 0);
+    for slot in out.iter_mut() {
+        if j == right.len() || (i < left.len() && compare(&right[j], &left[i]) != Ordering::Less) {
+            *slot = left[i].clone();
+            i += 1;
+        } else {
+            *slot = right[j].clone();
+            j += 1;
+        }
+    }
+}
+
+/// Top-down merge sort that sorts the slice in place, reusing a single
+/// auxiliary buffer for every merge instead of allocating at each level.
+pub fn merge_sort_buffered<T: Ord + Clone>(data: &mut [T]) {
+    merge_sort_buffered_by(data, T::cmp);
+}
+
+pub fn merge_sort_buffered_by<T, F>(data: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if data.len() < 2 {
+        return;
+    }
+    let mut buffer = data.to_vec();
+    buffered_rec(data, &mut buffer, 1, &mut compare);
+}
+
+pub fn hybrid_merge_sort<T: Ord + Clone>(data: &mut [T], cutoff: usize) {
+    hybrid_merge_sort_by(data, cutoff, T::cmp);
+}
+
+/// `merge_sort_buffered` that hands subarrays of at most `cutoff` elements to
+/// insertion sort, which is faster than recursing on short ranges.
+pub fn hybrid_merge_sort_by<T, F>(data: &mut [T], cutoff: usize, mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if data.len() < 2 {
+        return;
+    }
+    let mut buffer = data.to_vec();
+    buffered_rec(data, &mut buffer, cutoff, &mut compare);
+}
+
+pub(crate) fn buffered_rec<T, F>(data: &mut [T], buffer: &mut [T], cutoff: usize, compare: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if data.len() <= cutoff.max(1) {
+        insertion_sort_by(data, |a, b| compare(a, b));
+        return;
+    }
+    let mid = data.len() / 2;
+    buffered_rec(&mut data[..mid], &mut buffer[..mid], cutoff, compare);
+    buffered_rec(&mut data[mid..], &mut buffer[mid..], cutoff, compare);
+    // Already in order: the halves meet without overlap.
+    if compare(&data[mid], &data[mid - 1]) != Ordering::Less {
+        return;
+    }
+    buffer.clone_from_slice(data);
+    let (left, right) = buffer.split_at(mid);
+    merge_into(left, right, data, compare);
+}
+
+/// Iterative merge sort that merges runs of width 1, 2, 4, ... back and forth
+/// between the slice and one auxiliary buffer.
+pub fn merge_sort_bottom_up<T: Ord + Clone>(data: &mut [T]) {
+    merge_sort_bottom_up_by(data, T::cmp);
+}
+
+pub fn merge_sort_bottom_up_by<T, F>(data: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = data.len();
+    if len < 2 {
+        return;
+    }
+    let mut buffer = data.to_vec();
+    let mut sorted_in_buffer = false;
+    let mut width = 1;
+    while width < len {
+        let (src, dst): (&[T], &mut [T]) = if sorted_in_buffer {
+            (&buffer, data)
+        } else {
+            (data, &mut buffer)
+        };
+        for start in (0..len).step_by(2 * width) {
+            let mid = (start + width).min(len);
+            let end = (start + 2 * width).min(len);
+            merge_into(&src[start..mid], &src[mid..end], &mut dst[start..end], &mut compare);
+        }
+        sorted_in_buffer = !sorted_in_buffer;
+        width *= 2;
+    }
+    if sorted_in_buffer {
+        data.clone_from_slice(&buffer);
+    }
+}
+
+pub struct MergeSort;
+
+impl<T: Ord + Clone> Sorter<T> for MergeSort {
+    fn name(&self) -> &'static str {
+        "Merge Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        let sorted = merge_sort(data);
+        data.clone_from_slice(&sorted);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+pub struct BufferedMergeSort;
+
+impl<T: Ord + Clone> Sorter<T> for BufferedMergeSort {
+    fn name(&self) -> &'static str {
+        "Merge Sort (buffered)"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        merge_sort_buffered(data);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+pub struct BottomUpMergeSort;
+
+impl<T: Ord + Clone> Sorter<T> for BottomUpMergeSort {
+    fn name(&self) -> &'static str {
+        "Merge Sort (bottom-up)"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        merge_sort_bottom_up(data);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+pub struct HybridMergeSort {
+    pub cutoff: usize,
+}
+
+impl<T: Ord + Clone> Sorter<T> for HybridMergeSort {
+    fn name(&self) -> &'static str {
+        "Hybrid Merge Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        hybrid_merge_sort(data, self.cutoff);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Record {
+        age: u8,
+        name: String,
+    }
+
+    fn lengths() -> impl Iterator<Item = usize> {
+        [0, 1, 2, 3, 10, 57, 300, 1000].into_iter()
+    }
+
+    /// Every merge sort variant, driven through a comparator.
+    fn all_by<T: Clone>(data: &[T], mut compare: impl FnMut(&T, &T) -> Ordering) -> Vec<Vec<T>> {
+        let mut buffered = data.to_vec();
+        merge_sort_buffered_by(&mut buffered, &mut compare);
+        let mut hybrid = data.to_vec();
+        hybrid_merge_sort_by(&mut hybrid, 8, &mut compare);
+        let mut bottom_up = data.to_vec();
+        merge_sort_bottom_up_by(&mut bottom_up, &mut compare);
+        vec![merge_sort_by(data, &mut compare), buffered, hybrid, bottom_up]
+    }
+
+    #[test]
+    fn matches_slice_sort_on_integers() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for len in lengths() {
+            let data: Vec<i64> = (0..len).map(|_| rng.gen_range(-50..50)).collect();
+            let mut expected = data.clone();
+            expected.sort();
+            assert_eq!(merge_sort(&data), expected);
+            for sorted in all_by(&data, i64::cmp) {
+                assert_eq!(sorted, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_slice_sort_on_strings() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for len in lengths() {
+            let data: Vec<String> = (0..len).map(|_| format!("s{}", rng.gen_range(0..40))).collect();
+            let mut expected = data.clone();
+            expected.sort();
+            assert_eq!(merge_sort(&data), expected);
+            for sorted in all_by(&data, String::cmp) {
+                assert_eq!(sorted, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_stable_slice_sort_on_structs() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for len in lengths() {
+            let data: Vec<Record> = (0..len)
+                .map(|_| Record { age: rng.gen_range(0..20), name: format!("n{}", rng.gen_range(0..50)) })
+                .collect();
+
+            let mut expected = data.clone();
+            expected.sort();
+            assert_eq!(merge_sort(&data), expected);
+
+            // Both sorts are stable, so records with equal ages keep their input order.
+            let mut expected = data.clone();
+            expected.sort_by_key(|r| r.age);
+            assert_eq!(merge_sort_by_key(&data, |r| r.age), expected);
+            for sorted in all_by(&data, |a, b| a.age.cmp(&b.age)) {
+                assert_eq!(sorted, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_slice_sort_on_floats_by_total_cmp() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for len in lengths() {
+            let mut data: Vec<f64> = (0..len).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            data.extend([f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -0.0, 0.0]);
+            let mut expected = data.clone();
+            expected.sort_by(f64::total_cmp);
+            let bits = |v: &[f64]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+            for sorted in all_by(&data, f64::total_cmp) {
+                assert_eq!(bits(&sorted), bits(&expected));
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::cmp::Ordering;
+use std::thread;
+
+use crate::merge::{buffered_rec, merge_into};
+use crate::radix::{key_bounds, Digits, RadixKey};
+use crate::sorter::Sorter;
+
+/// Below this length a subproblem is sorted on the current thread; spawning
+/// costs more than it saves.
+pub const PARALLEL_CUTOFF: usize = 1 << 14;
+
+/// Number of threads to use when the caller does not choose one.
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+pub fn parallel_merge_sort<T: Ord + Clone + Send>(data: &mut [T], threads: usize) {
+    parallel_merge_sort_by(data, threads, T::cmp);
+}
+
+/// Fork-join merge sort: each half is sorted on its own thread until the
+/// thread budget is used up or the half falls below `PARALLEL_CUTOFF`.
+pub fn parallel_merge_sort_by<T, F>(data: &mut [T], threads: usize, compare: F)
+where
+    T: Clone + Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if data.len() < 2 {
+        return;
+    }
+    let mut buffer = data.to_vec();
+    parallel_merge_rec(data, &mut buffer, threads.max(1), &compare);
+}
+
+fn parallel_merge_rec<T, F>(data: &mut [T], buffer: &mut [T], threads: usize, compare: &F)
+where
+    T: Clone + Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads == 1 || data.len() <= PARALLEL_CUTOFF {
+        buffered_rec(data, buffer, 1, &mut |a: &T, b: &T| compare(a, b));
+        return;
+    }
+    let mid = data.len() / 2;
+    {
+        let (data_left, data_right) = data.split_at_mut(mid);
+        let (buffer_left, buffer_right) = buffer.split_at_mut(mid);
+        let left_threads = threads / 2;
+        thread::scope(|s| {
+            s.spawn(|| parallel_merge_rec(data_left, buffer_left, left_threads, compare));
+            parallel_merge_rec(data_right, buffer_right, threads - left_threads, compare);
+        });
+    }
+    if compare(&data[mid], &data[mid - 1]) != Ordering::Less {
+        return;
+    }
+    buffer.clone_from_slice(data);
+    let (left, right) = buffer.split_at(mid);
+    merge_into(left, right, data, &mut |a: &T, b: &T| compare(a, b));
+}
+
+/// Counting LSD radix sort where every pass splits the input into one chunk
+/// per thread. Each thread builds a histogram of its chunk; the histograms are
+/// combined into per-thread output offsets so the scatter step can also run in
+/// parallel while staying stable.
+pub fn parallel_radix_sort<T: RadixKey + Send + Sync>(nums: &mut [T], radix: u32, threads: usize) {
+    let digits = Digits::new(radix);
+    let threads = threads.max(1);
+    let chunk_len = nums.len().div_ceil(threads).max(1);
+    let bounds = thread::scope(|s| {
+        let handles: Vec<_> = nums
+            .chunks(chunk_len)
+            .map(|chunk| s.spawn(move || key_bounds(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|h| h.join().unwrap())
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+    });
+    let (min, max) = match bounds {
+        Some(bounds) => bounds,
+        None => return,
+    };
+    let passes = digits.passes(max - min);
+    if passes == 0 {
+        return;
+    }
+
+    let mut scratch: Vec<T> = nums.to_vec();
+    let (mut src, mut dst): (&mut [T], &mut [T]) = (nums, &mut scratch);
+    for pass in 0..passes {
+        let digit_of = |x: &T| digits.get(x.radix_key() - min, pass);
+        let counts: Vec<Vec<usize>> = thread::scope(|s| {
+            let handles: Vec<_> = src
+                .chunks(chunk_len)
+                .map(|chunk| {
+                    s.spawn(move || {
+                        let mut counts = vec![0usize; radix as usize];
+                        chunk.iter().for_each(|x| counts[digit_of(x)] += 1);
+                        counts
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        // Carve the output into (digit, chunk) regions in digit-major order, so
+        // chunk t writes its elements of digit d right after chunk t-1's.
+        let mut regions: Vec<Vec<&mut [T]>> = counts.iter().map(|_| Vec::with_capacity(radix as usize)).collect();
+        let mut rest: &mut [T] = dst;
+        for digit in 0..radix as usize {
+            for (chunk, chunk_counts) in counts.iter().enumerate() {
+                let (region, tail) = rest.split_at_mut(chunk_counts[digit]);
+                regions[chunk].push(region);
+                rest = tail;
+            }
+        }
+
+        thread::scope(|s| {
+            for (chunk, mut chunk_regions) in src.chunks(chunk_len).zip(regions) {
+                s.spawn(move || {
+                    for &x in chunk {
+                        let region = std::mem::take(&mut chunk_regions[digit_of(&x)]);
+                        let (slot, tail) = region.split_first_mut().unwrap();
+                        *slot = x;
+                        chunk_regions[digit_of(&x)] = tail;
+                    }
+                });
+            }
+        });
+        std::mem::swap(&mut src, &mut dst);
+    }
+
+    if passes % 2 == 1 {
+        dst.copy_from_slice(src);
+    }
+}
+
+pub struct ParallelMergeSort {
+    pub threads: usize,
+}
+
+impl ParallelMergeSort {
+    pub fn new() -> Self {
+        ParallelMergeSort { threads: default_threads() }
+    }
+}
+
+impl Default for ParallelMergeSort {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord + Clone + Send> Sorter<T> for ParallelMergeSort {
+    fn name(&self) -> &'static str {
+        "Parallel Merge Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        parallel_merge_sort(data, self.threads);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+pub struct ParallelRadixSort {
+    pub radix: u32,
+    pub threads: usize,
+}
+
+impl ParallelRadixSort {
+    pub fn new(radix: u32) -> Self {
+        ParallelRadixSort { radix, threads: default_threads() }
+    }
+}
+
+impl<T: RadixKey + Send + Sync> Sorter<T> for ParallelRadixSort {
+    fn name(&self) -> &'static str {
+        "Parallel Radix Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        parallel_radix_sort(data, self.radix, self.threads);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
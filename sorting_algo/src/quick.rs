@@ -0,0 +1,442 @@
+use std::cmp::Ordering;
+
+use rand::Rng;
+
+use crate::heap::heapsort_by;
+use crate::insertion::insertion_sort_by;
+use crate::sorter::Sorter;
+
+/// How the range is split around the pivot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Partition {
+    /// Single scan keeping `< pivot` on the left; degrades on many duplicates.
+    Lomuto,
+    /// Two scans from both ends that stop on equal keys, so duplicates split evenly.
+    Hoare,
+    /// Dijkstra's Dutch national flag: `<`, `==` and `>` regions, equal keys are
+    /// never recursed into.
+    ThreeWay,
+}
+
+/// Which element becomes the pivot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pivot {
+    Last,
+    Middle,
+    MedianOfThree,
+    Random,
+}
+
+/// Ranges at or below this length are finished with insertion sort by introsort.
+const INSERTION_THRESHOLD: usize = 16;
+
+pub fn quicksort<T: Ord>(v: &mut [T], partition: Partition, pivot: Pivot) {
+    quicksort_by(v, partition, pivot, T::cmp);
+}
+
+pub fn quicksort_by<T, F>(v: &mut [T], partition: Partition, pivot: Pivot, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quicksort_rec(v, partition, pivot, 1, &mut compare);
+}
+
+pub fn hybrid_quicksort<T: Ord>(v: &mut [T], cutoff: usize) {
+    hybrid_quicksort_by(v, cutoff, T::cmp);
+}
+
+/// Median-of-three quicksort that leaves ranges of at most `cutoff` elements
+/// to insertion sort.
+pub fn hybrid_quicksort_by<T, F>(v: &mut [T], cutoff: usize, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quicksort_rec(v, Partition::Hoare, Pivot::MedianOfThree, cutoff, &mut compare);
+}
+
+pub fn quicksort_lomuto<T: Ord>(v: &mut [T]) {
+    quicksort(v, Partition::Lomuto, Pivot::Last);
+}
+
+pub fn quicksort_hoare<T: Ord>(v: &mut [T]) {
+    quicksort(v, Partition::Hoare, Pivot::Middle);
+}
+
+pub fn quicksort_three_way<T: Ord>(v: &mut [T]) {
+    quicksort(v, Partition::ThreeWay, Pivot::Middle);
+}
+
+pub fn quicksort_median_of_three<T: Ord>(v: &mut [T]) {
+    quicksort(v, Partition::Hoare, Pivot::MedianOfThree);
+}
+
+pub fn quicksort_random<T: Ord>(v: &mut [T]) {
+    quicksort(v, Partition::Lomuto, Pivot::Random);
+}
+
+// Recurses into the smaller side and loops on the larger one, so the stack
+// depth stays logarithmic even when the partitions are lopsided.
+fn quicksort_rec<T, F>(mut v: &mut [T], partition: Partition, pivot: Pivot, cutoff: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while v.len() > cutoff.max(1) {
+        let (left_end, right_start) = partition_around(v, partition, pivot, compare);
+        let (left, rest) = std::mem::take(&mut v).split_at_mut(left_end);
+        let right = &mut rest[right_start - left_end..];
+        if left.len() < right.len() {
+            quicksort_rec(left, partition, pivot, cutoff, compare);
+            v = right;
+        } else {
+            quicksort_rec(right, partition, pivot, cutoff, compare);
+            v = left;
+        }
+    }
+    insertion_sort_by(v, |a, b| compare(a, b));
+}
+
+/// Partitions `v` and returns `(a, b)` such that `v[..a]` sorts before and
+/// `v[b..]` sorts after everything in `v[a..b]`, which is already in place.
+pub(crate) fn partition_around<T, F>(v: &mut [T], partition: Partition, pivot: Pivot, compare: &mut F) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let p = choose_pivot(v, pivot, compare);
+    match partition {
+        Partition::Lomuto => {
+            let last = v.len() - 1;
+            v.swap(p, last);
+            let mid = partition_lomuto(v, compare);
+            (mid, mid + 1)
+        }
+        Partition::Hoare => {
+            v.swap(p, 0);
+            let mid = partition_hoare(v, compare);
+            (mid, mid + 1)
+        }
+        Partition::ThreeWay => {
+            v.swap(p, 0);
+            partition_three_way(v, compare)
+        }
+    }
+}
+
+fn choose_pivot<T, F>(v: &[T], pivot: Pivot, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    match pivot {
+        Pivot::Last => len - 1,
+        Pivot::Middle => len / 2,
+        Pivot::MedianOfThree => median_of_three(v, 0, len / 2, len - 1, compare),
+        Pivot::Random => rand::thread_rng().gen_range(0..len),
+    }
+}
+
+fn median_of_three<T, F>(v: &[T], a: usize, b: usize, c: usize, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let less = |x: usize, y: usize, compare: &mut F| compare(&v[x], &v[y]) == Ordering::Less;
+    if less(a, b, compare) {
+        if less(b, c, compare) {
+            b
+        } else if less(a, c, compare) {
+            c
+        } else {
+            a
+        }
+    } else if less(a, c, compare) {
+        a
+    } else if less(b, c, compare) {
+        c
+    } else {
+        b
+    }
+}
+
+/// Pivot is `v[len - 1]`; returns its final index.
+fn partition_lomuto<T, F>(v: &mut [T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let last = v.len() - 1;
+    let mut store = 0;
+    for i in 0..last {
+        if compare(&v[i], &v[last]) == Ordering::Less {
+            v.swap(i, store);
+            store += 1;
+        }
+    }
+    v.swap(store, last);
+    store
+}
+
+/// Pivot is `v[0]`; returns its final index.
+fn partition_hoare<T, F>(v: &mut [T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let (mut i, mut j) = (1, v.len() - 1);
+    loop {
+        while i <= j && compare(&v[i], &v[0]) == Ordering::Less {
+            i += 1;
+        }
+        while i <= j && compare(&v[0], &v[j]) == Ordering::Less {
+            j -= 1;
+        }
+        if i >= j {
+            break;
+        }
+        v.swap(i, j);
+        i += 1;
+        j -= 1;
+    }
+    v.swap(0, j);
+    j
+}
+
+/// Pivot is `v[0]`; returns the bounds of the run equal to it.
+pub(crate) fn partition_three_way<T, F>(v: &mut [T], compare: &mut F) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    // v[lt..i] equals the pivot, v[gt..] is greater, v[i..gt] is unseen.
+    let (mut lt, mut i, mut gt) = (0, 1, v.len());
+    while i < gt {
+        match compare(&v[i], &v[lt]) {
+            Ordering::Less => {
+                v.swap(lt, i);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                gt -= 1;
+                v.swap(i, gt);
+            }
+            Ordering::Equal => i += 1,
+        }
+    }
+    (lt, gt)
+}
+
+pub fn introsort<T: Ord>(v: &mut [T]) {
+    introsort_by(v, T::cmp);
+}
+
+/// Median-of-three quicksort that switches to heapsort once the recursion is
+/// deeper than `2 * log2(n)`, bounding the worst case at O(n log n), and to
+/// insertion sort for short ranges.
+pub fn introsort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let depth_limit = 2 * (usize::BITS - v.len().leading_zeros());
+    introsort_rec(v, depth_limit, &mut compare);
+}
+
+fn introsort_rec<T, F>(mut v: &mut [T], mut depth_limit: u32, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while v.len() > INSERTION_THRESHOLD {
+        if depth_limit == 0 {
+            heapsort_by::<T, _, 2>(v, |a: &T, b: &T| compare(a, b));
+            return;
+        }
+        depth_limit -= 1;
+        let (left_end, right_start) = partition_around(v, Partition::Hoare, Pivot::MedianOfThree, compare);
+        let (left, rest) = std::mem::take(&mut v).split_at_mut(left_end);
+        let right = &mut rest[right_start - left_end..];
+        if left.len() < right.len() {
+            introsort_rec(left, depth_limit, compare);
+            v = right;
+        } else {
+            introsort_rec(right, depth_limit, compare);
+            v = left;
+        }
+    }
+    insertion_sort_by(v, |a, b| compare(a, b));
+}
+
+pub struct QuickSort {
+    pub partition: Partition,
+    pub pivot: Pivot,
+}
+
+impl QuickSort {
+    pub const LOMUTO: QuickSort = QuickSort { partition: Partition::Lomuto, pivot: Pivot::Last };
+    pub const HOARE: QuickSort = QuickSort { partition: Partition::Hoare, pivot: Pivot::Middle };
+    pub const THREE_WAY: QuickSort = QuickSort { partition: Partition::ThreeWay, pivot: Pivot::Middle };
+    pub const MEDIAN_OF_THREE: QuickSort = QuickSort { partition: Partition::Hoare, pivot: Pivot::MedianOfThree };
+    pub const RANDOM: QuickSort = QuickSort { partition: Partition::Lomuto, pivot: Pivot::Random };
+}
+
+impl<T: Ord> Sorter<T> for QuickSort {
+    fn name(&self) -> &'static str {
+        match (self.partition, self.pivot) {
+            (Partition::Lomuto, Pivot::Last) => "Quicksort (Lomuto)",
+            (Partition::Hoare, Pivot::Middle) => "Quicksort (Hoare)",
+            (Partition::ThreeWay, Pivot::Middle) => "Quicksort (3-way)",
+            (Partition::Hoare, Pivot::MedianOfThree) => "Quicksort (median-of-three)",
+            (Partition::Lomuto, Pivot::Random) => "Quicksort (random pivot)",
+            _ => "Quicksort",
+        }
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        quicksort(data, self.partition, self.pivot);
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+}
+
+pub struct IntroSort;
+
+impl<T: Ord> Sorter<T> for IntroSort {
+    fn name(&self) -> &'static str {
+        "Introsort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        introsort(data);
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+}
+
+pub struct HybridQuickSort {
+    pub cutoff: usize,
+}
+
+impl<T: Ord> Sorter<T> for HybridQuickSort {
+    fn name(&self) -> &'static str {
+        "Hybrid Quicksort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        hybrid_quicksort(data, self.cutoff);
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn inputs() -> Vec<(&'static str, Vec<i64>)> {
+        let mut rng = StdRng::seed_from_u64(1);
+        let n = 2000;
+        vec![
+            ("empty", vec![]),
+            ("single", vec![1]),
+            ("random", (0..n).map(|_| rng.gen_range(-10_000..10_000)).collect()),
+            ("duplicate-heavy", (0..n).map(|_| rng.gen_range(0..4)).collect()),
+            ("all equal", vec![5; n as usize]),
+            ("sorted", (0..n).collect()),
+            ("reversed", (0..n).rev().collect()),
+        ]
+    }
+
+    #[test]
+    fn every_variant_sorts_every_input() {
+        let sorters: [&dyn Sorter<i64>; 7] = [
+            &QuickSort::LOMUTO,
+            &QuickSort::HOARE,
+            &QuickSort::THREE_WAY,
+            &QuickSort::MEDIAN_OF_THREE,
+            &QuickSort::RANDOM,
+            &IntroSort,
+            &HybridQuickSort { cutoff: 16 },
+        ];
+        for (input_name, input) in inputs() {
+            let mut expected = input.clone();
+            expected.sort();
+            for sorter in sorters {
+                let mut data = input.clone();
+                sorter.sort(&mut data);
+                assert_eq!(data, expected, "{} on {} input", sorter.name(), input_name);
+            }
+        }
+    }
+
+    #[test]
+    fn partitions_split_around_the_pivot() {
+        for partition in [Partition::Lomuto, Partition::Hoare, Partition::ThreeWay] {
+            for pivot in [Pivot::Last, Pivot::Middle, Pivot::MedianOfThree, Pivot::Random] {
+                for (input_name, mut v) in inputs().into_iter().filter(|(_, v)| v.len() > 1) {
+                    let (left_end, right_start) = partition_around(&mut v, partition, pivot, &mut i64::cmp);
+                    let context = format!("{:?}/{:?} on {} input", partition, pivot, input_name);
+                    assert!(left_end < right_start && right_start <= v.len(), "{}", context);
+                    let max_left = v[..left_end].iter().max();
+                    let min_right = v[right_start..].iter().min();
+                    let middle = &v[left_end..right_start];
+                    assert!(max_left.is_none_or(|l| middle.iter().all(|m| l <= m)), "{}", context);
+                    assert!(min_right.is_none_or(|r| middle.iter().all(|m| m <= r)), "{}", context);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn introsort_falls_back_to_heapsort() {
+        for (input_name, input) in inputs() {
+            let mut expected = input.clone();
+            expected.sort();
+            // No quicksort levels allowed, so anything longer than the
+            // insertion threshold goes straight to heapsort.
+            for depth_limit in [0, 1, 2] {
+                let mut data = input.clone();
+                introsort_rec(&mut data, depth_limit, &mut i64::cmp);
+                assert_eq!(data, expected, "depth limit {} on {} input", depth_limit, input_name);
+            }
+        }
+    }
+
+    #[test]
+    fn introsort_is_n_log_n_on_a_median_of_three_killer() {
+        // Musser's median-of-3 killer drives plain median-of-three quicksort
+        // quadratic; introsort's depth limit must cap the comparisons.
+        let k = 2048;
+        let mut killer = vec![0i64; 2 * k];
+        for i in 1..=k {
+            if i % 2 == 1 {
+                killer[i - 1] = i as i64;
+                killer[i] = (k + i) as i64;
+            }
+            killer[k + i - 1] = 2 * i as i64;
+        }
+        let mut expected = killer.clone();
+        expected.sort();
+        let mut comparisons = 0usize;
+        introsort_by(&mut killer, |a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+        assert_eq!(killer, expected);
+        let n = (2 * k) as f64;
+        assert!((comparisons as f64) < 10.0 * n * n.log2(), "{} comparisons for n = {}", comparisons, n);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::sorter::Sorter;
+
+/// Maps a value onto an unsigned key whose ordering matches the value's, so
+/// signed integers can be bucketed digit by digit.
+pub trait RadixKey: Copy {
+    fn radix_key(self) -> u128;
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            fn radix_key(self) -> u128 {
+                self as u128
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_radix_key_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl RadixKey for $t {
+            fn radix_key(self) -> u128 {
+                // Flipping the sign bit moves negatives below the positives.
+                ((self as $u) ^ (1 << (<$u>::BITS - 1))) as u128
+            }
+        }
+    )*};
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+/// Extracts successive digits of a key, using shifts when the radix is a power of two.
+#[derive(Clone, Copy)]
+pub(crate) struct Digits {
+    radix: u128,
+    shift: Option<u32>,
+}
+
+impl Digits {
+    pub(crate) fn new(radix: u32) -> Self {
+        assert!(radix >= 2, "radix must be at least 2, got {}", radix);
+        let shift = radix.is_power_of_two().then(|| radix.trailing_zeros());
+        Digits { radix: radix as u128, shift }
+    }
+
+    pub(crate) fn get(&self, key: u128, pass: u32) -> usize {
+        match self.shift {
+            Some(shift) => ((key >> (shift * pass)) & (self.radix - 1)) as usize,
+            None => ((key / self.radix.pow(pass)) % self.radix) as usize,
+        }
+    }
+
+    /// Number of passes needed to cover every digit of `range`.
+    pub(crate) fn passes(&self, range: u128) -> u32 {
+        let mut passes = 0;
+        let mut rest = range;
+        while rest > 0 {
+            rest /= self.radix;
+            passes += 1;
+        }
+        passes
+    }
+}
+
+/// LSD radix sort over any `RadixKey`. Keys are offset by the minimum so the
+/// number of passes only depends on the spread of the input, not its magnitude.
+pub fn radix_sort<T: RadixKey>(nums: &mut [T], radix: u32) {
+    let digits = Digits::new(radix);
+    let (min, max) = match key_bounds(nums) {
+        Some(bounds) => bounds,
+        None => return,
+    };
+
+    let mut buckets: Vec<Vec<T>> = vec![Vec::new(); radix as usize];
+    for pass in 0..digits.passes(max - min) {
+        nums.iter()
+            .for_each(|&x| buckets[digits.get(x.radix_key() - min, pass)].push(x));
+        buckets
+            .iter()
+            .flat_map(|b| b.iter())
+            .zip(nums.iter_mut())
+            .for_each(|(&x, y)| *y = x);
+        buckets.iter_mut().for_each(|b| b.clear());
+    }
+}
+
+/// LSD radix sort that places elements through a per-pass histogram and prefix
+/// sums instead of bucket vectors, so the only allocation is one scratch buffer.
+pub fn counting_radix_sort<T: RadixKey>(nums: &mut [T], radix: u32) {
+    let digits = Digits::new(radix);
+    let (min, max) = match key_bounds(nums) {
+        Some(bounds) => bounds,
+        None => return,
+    };
+    let passes = digits.passes(max - min);
+    if passes == 0 {
+        return;
+    }
+
+    let mut scratch: Vec<T> = nums.to_vec();
+    let mut counts = vec![0usize; radix as usize];
+    let (mut src, mut dst): (&mut [T], &mut [T]) = (nums, &mut scratch);
+    for pass in 0..passes {
+        counts.iter_mut().for_each(|c| *c = 0);
+        for &x in src.iter() {
+            counts[digits.get(x.radix_key() - min, pass)] += 1;
+        }
+        let mut offset = 0;
+        for c in counts.iter_mut() {
+            let count = *c;
+            *c = offset;
+            offset += count;
+        }
+        for &x in src.iter() {
+            let digit = digits.get(x.radix_key() - min, pass);
+            dst[counts[digit]] = x;
+            counts[digit] += 1;
+        }
+        std::mem::swap(&mut src, &mut dst);
+    }
+
+    // After an odd number of passes the sorted data lives in the scratch buffer.
+    if passes % 2 == 1 {
+        dst.copy_from_slice(src);
+    }
+}
+
+pub fn radix_sort_base10<T: RadixKey>(nums: &mut [T]) {
+    radix_sort(nums, 10);
+}
+
+pub(crate) fn key_bounds<T: RadixKey>(nums: &[T]) -> Option<(u128, u128)> {
+    let mut keys = nums.iter().map(|x| x.radix_key());
+    let first = keys.next()?;
+    Some(keys.fold((first, first), |(min, max), k| (min.min(k), max.max(k))))
+}
+
+pub struct RadixSort {
+    pub radix: u32,
+}
+
+impl<T: RadixKey> Sorter<T> for RadixSort {
+    fn name(&self) -> &'static str {
+        match self.radix {
+            10 => "Radix Sort",
+            16 => "Radix Sort (base 16)",
+            256 => "Radix Sort (base 256)",
+            65536 => "Radix Sort (base 65536)",
+            _ => "Radix Sort (custom base)",
+        }
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        radix_sort(data, self.radix);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+pub struct CountingRadixSort {
+    pub radix: u32,
+}
+
+impl<T: RadixKey> Sorter<T> for CountingRadixSort {
+    fn name(&self) -> &'static str {
+        match self.radix {
+            10 => "Counting Radix Sort",
+            16 => "Counting Radix Sort (base 16)",
+            256 => "Counting Radix Sort (base 256)",
+            65536 => "Counting Radix Sort (base 65536)",
+            _ => "Counting Radix Sort (custom base)",
+        }
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        counting_radix_sort(data, self.radix);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const RADIXES: [u32; 4] = [10, 16, 256, 65536];
+
+    /// Checks both radix sorts at every radix against `slice::sort`.
+    fn check<T: RadixKey + Ord + std::fmt::Debug>(data: &[T]) {
+        let mut expected = data.to_vec();
+        expected.sort();
+        for radix in RADIXES {
+            let mut sorted = data.to_vec();
+            radix_sort(&mut sorted, radix);
+            assert_eq!(sorted, expected, "radix_sort with radix {}", radix);
+            let mut sorted = data.to_vec();
+            counting_radix_sort(&mut sorted, radix);
+            assert_eq!(sorted, expected, "counting_radix_sort with radix {}", radix);
+        }
+    }
+
+    #[test]
+    fn negatives_and_the_full_i64_range() {
+        let mut rng = StdRng::seed_from_u64(1);
+        check(&(0..1000).map(|_| rng.gen_range(-5000i64..5000)).collect::<Vec<_>>());
+        check(&[-1i64, -100, -3, -3, -2]);
+        check(&[i64::MAX, 0, i64::MIN, -1, 1, i64::MIN, i64::MAX]);
+        check(&(0..1000).map(|_| rng.gen::<i64>()).chain([i64::MIN, i64::MAX]).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn other_key_types() {
+        let mut rng = StdRng::seed_from_u64(2);
+        check(&(0..500).map(|_| rng.gen::<u32>()).chain([0, u32::MAX]).collect::<Vec<_>>());
+        check(&(0..500).map(|_| rng.gen::<i32>()).chain([i32::MIN, i32::MAX]).collect::<Vec<_>>());
+        check(&(0..500).map(|_| rng.gen::<u64>()).chain([0, u64::MAX]).collect::<Vec<_>>());
+        check(&(0..500).map(|_| rng.gen::<u128>()).chain([0, u128::MAX, u128::MAX]).collect::<Vec<_>>());
+        check(&[3u8, 255, 0, 7]);
+    }
+
+    #[test]
+    fn empty_single_and_all_equal_inputs() {
+        check::<i64>(&[]);
+        check(&[42i64]);
+        check(&[i64::MIN; 100]);
+        check(&[u128::MAX; 100]);
+        // max - min is zero, so no digit pass runs at all.
+        assert_eq!(key_bounds(&[u128::MAX; 3]), Some((u128::MAX, u128::MAX)));
+        assert_eq!(Digits::new(256).passes(0), 0);
+    }
+
+    #[test]
+    fn passes_follow_the_spread_not_the_key_width() {
+        assert_eq!(Digits::new(10).passes(9), 1);
+        assert_eq!(Digits::new(10).passes(10), 2);
+        assert_eq!(Digits::new(256).passes(255), 1);
+        assert_eq!(Digits::new(256).passes(256), 2);
+        assert_eq!(Digits::new(256).passes(u64::MAX as u128), 8);
+        assert_eq!(Digits::new(65536).passes(u128::MAX), 8);
+        assert_eq!(Digits::new(10).passes(u128::MAX), 39);
+
+        // Large keys close together need a single pass whatever their width.
+        let (min, max) = key_bounds(&[i64::MAX - 3, i64::MAX, i64::MAX - 1]).unwrap();
+        assert_eq!(Digits::new(256).passes(max - min), 1);
+        let (min, max) = key_bounds(&[u128::MAX - 200, u128::MAX]).unwrap();
+        assert_eq!(Digits::new(16).passes(max - min), 2);
+        check(&[u128::MAX - 200, u128::MAX, u128::MAX - 17]);
+    }
+
+    #[test]
+    fn digits_match_division() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for radix in [10u32, 16, 256, 65536] {
+            let digits = Digits::new(radix);
+            for _ in 0..100 {
+                let key: u128 = rng.gen();
+                for pass in 0..digits.passes(key) {
+                    assert_eq!(digits.get(key, pass) as u128, key / (radix as u128).pow(pass) % radix as u128);
+                }
+            }
+        }
+    }
+}
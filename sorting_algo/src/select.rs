@@ -0,0 +1,140 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::insertion::insertion_sort;
+use crate::quick::{introsort, partition_around, partition_three_way, Partition, Pivot};
+
+/// Rearranges `v` so that `v[k]` is the element that would be there after
+/// sorting, everything before it is `<=` and everything after is `>=`, and
+/// returns it. Expected O(n) thanks to a random pivot.
+///
+/// Panics if `k >= v.len()`.
+pub fn quickselect<T: Ord>(v: &mut [T], k: usize) -> &T {
+    assert!(k < v.len(), "select index {} out of range for length {}", k, v.len());
+    let (mut lo, mut hi) = (0, v.len());
+    loop {
+        let (eq_start, eq_end) = partition_around(&mut v[lo..hi], Partition::ThreeWay, Pivot::Random, &mut T::cmp);
+        let (eq_start, eq_end) = (lo + eq_start, lo + eq_end);
+        if k < eq_start {
+            hi = eq_start;
+        } else if k >= eq_end {
+            lo = eq_end;
+        } else {
+            return &v[k];
+        }
+    }
+}
+
+/// Same contract as `quickselect`, but picks the pivot as the median of the
+/// medians of groups of five, which guarantees O(n) in the worst case.
+pub fn median_of_medians_select<T: Ord>(v: &mut [T], k: usize) -> &T {
+    assert!(k < v.len(), "select index {} out of range for length {}", k, v.len());
+    let (mut lo, mut hi) = (0, v.len());
+    loop {
+        let range = &mut v[lo..hi];
+        if range.len() <= 5 {
+            insertion_sort(range);
+            return &v[k];
+        }
+        let pivot = median_of_medians(range);
+        range.swap(0, pivot);
+        let (eq_start, eq_end) = partition_three_way(range, &mut T::cmp);
+        let (eq_start, eq_end) = (lo + eq_start, lo + eq_end);
+        if k < eq_start {
+            hi = eq_start;
+        } else if k >= eq_end {
+            lo = eq_end;
+        } else {
+            return &v[k];
+        }
+    }
+}
+
+/// Index of the median of medians of `v`. Moves each group's median to the
+/// front of `v` along the way.
+fn median_of_medians<T: Ord>(v: &mut [T]) -> usize {
+    let groups = v.len().div_ceil(5);
+    for g in 0..groups {
+        let start = g * 5;
+        let end = (start + 5).min(v.len());
+        insertion_sort(&mut v[start..end]);
+        v.swap(g, start + (end - start) / 2);
+    }
+    median_of_medians_select(&mut v[..groups], groups / 2);
+    groups / 2
+}
+
+/// Puts the `k` smallest elements of `v` in sorted order at the front; the
+/// order of the rest is unspecified.
+pub fn partial_sort<T: Ord>(v: &mut [T], k: usize) {
+    let k = k.min(v.len());
+    if k == 0 {
+        return;
+    }
+    if k < v.len() {
+        quickselect(v, k - 1);
+    }
+    introsort(&mut v[..k]);
+}
+
+/// The `k` largest items of a stream in descending order, holding only `k`
+/// items at a time in a min-heap whose root is the smallest one kept so far.
+pub fn top_k<T: Ord, I: IntoIterator<Item = T>>(items: I, k: usize) -> Vec<T> {
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut heap: BinaryHeap<Reverse<T>> = BinaryHeap::with_capacity(k);
+    for item in items {
+        if heap.len() < k {
+            heap.push(Reverse(item));
+        } else if let Some(mut smallest) = heap.peek_mut() {
+            if item > smallest.0 {
+                smallest.0 = item;
+            }
+        }
+    }
+    // Ascending order of `Reverse` is descending order of the items.
+    heap.into_sorted_vec().into_iter().map(|Reverse(item)| item).collect()
+}
+
+/// The `k` smallest items of a stream in ascending order.
+pub fn bottom_k<T: Ord, I: IntoIterator<Item = T>>(items: I, k: usize) -> Vec<T> {
+    top_k(items.into_iter().map(Reverse), k).into_iter().map(|Reverse(item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn top_and_bottom_k_match_sorting() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let data: Vec<i64> = (0..5000).map(|_| rng.gen_range(-500..500)).collect();
+        let mut sorted = data.clone();
+        sorted.sort();
+        for k in [0, 1, 7, 100, 5000, 6000] {
+            let k_kept = k.min(data.len());
+            assert_eq!(bottom_k(data.iter().copied(), k), sorted[..k_kept]);
+            let mut top = sorted[sorted.len() - k_kept..].to_vec();
+            top.reverse();
+            assert_eq!(top_k(data.iter().copied(), k), top);
+        }
+    }
+
+    #[test]
+    fn top_k_holds_only_k_items() {
+        // Each live item holds a clone of `live`, so the count is the k kept
+        // items, the one in hand and `live` itself unless the stream is buffered.
+        use std::rc::Rc;
+        let live = Rc::new(());
+        let stream = (0..100_000u32).map(|i| {
+            assert!(Rc::strong_count(&live) <= 12, "more than k items alive at once");
+            (i.wrapping_mul(2_654_435_761), Rc::clone(&live))
+        });
+        let top = top_k(stream, 10);
+        assert_eq!(top.len(), 10);
+        assert!(top.windows(2).all(|w| w[0].0 >= w[1].0));
+    }
+}
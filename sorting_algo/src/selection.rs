@@ -0,0 +1,133 @@
+use std::cmp::Ordering;
+
+use crate::sorter::Sorter;
+
+pub fn selection_sort<T: Ord>(arr: &mut [T]) {
+    selection_sort_by(arr, T::cmp);
+}
+
+pub fn selection_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    for i in 0..len {
+        let mut min_idx = i;
+        for j in (i + 1)..len {
+            if compare(&arr[j], &arr[min_idx]) == Ordering::Less {
+                min_idx = j;
+            }
+        }
+        arr.swap(i, min_idx);
+    }
+}
+
+pub fn selection_sort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    selection_sort_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub struct SelectionSort;
+
+impl<T: Ord> Sorter<T> for SelectionSort {
+    fn name(&self) -> &'static str {
+        "Selection Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        selection_sort(data);
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Record {
+        age: u8,
+        name: String,
+    }
+
+    fn lengths() -> impl Iterator<Item = usize> {
+        [0, 1, 2, 3, 10, 57, 300].into_iter()
+    }
+
+    #[test]
+    fn matches_slice_sort_on_integers() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for len in lengths() {
+            let mut data: Vec<i64> = (0..len).map(|_| rng.gen_range(-50..50)).collect();
+            let mut expected = data.clone();
+            expected.sort();
+            selection_sort(&mut data);
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    fn matches_slice_sort_on_strings() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for len in lengths() {
+            let mut data: Vec<String> = (0..len).map(|_| format!("s{}", rng.gen_range(0..40))).collect();
+            let mut expected = data.clone();
+            expected.sort();
+            selection_sort(&mut data);
+            assert_eq!(data, expected);
+        }
+    }
+
+    #[test]
+    fn matches_slice_sort_on_structs() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for len in lengths() {
+            let data: Vec<Record> = (0..len)
+                .map(|_| Record { age: rng.gen_range(0..20), name: format!("n{}", rng.gen_range(0..5)) })
+                .collect();
+
+            let (mut sorted, mut expected) = (data.clone(), data.clone());
+            selection_sort(&mut sorted);
+            expected.sort();
+            assert_eq!(sorted, expected);
+
+            // Selection sort is unstable, so only the keys have a defined order.
+            let mut by_key = data.clone();
+            selection_sort_by_key(&mut by_key, |r| r.age);
+            let mut expected = data.clone();
+            expected.sort_by_key(|r| r.age);
+            assert_eq!(by_key.iter().map(|r| r.age).collect::<Vec<_>>(), expected.iter().map(|r| r.age).collect::<Vec<_>>());
+
+            let mut reversed = data.clone();
+            selection_sort_by(&mut reversed, |a, b| b.cmp(a));
+            let mut expected = data;
+            expected.sort_by(|a, b| b.cmp(a));
+            assert_eq!(reversed, expected);
+        }
+    }
+
+    #[test]
+    fn matches_slice_sort_on_floats_by_total_cmp() {
+        let mut rng = StdRng::seed_from_u64(4);
+        for len in lengths() {
+            let mut data: Vec<f64> = (0..len).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            data.extend([f64::NAN, f64::INFINITY, f64::NEG_INFINITY, -0.0, 0.0]);
+            let mut expected = data.clone();
+            expected.sort_by(f64::total_cmp);
+            selection_sort_by(&mut data, f64::total_cmp);
+            assert_eq!(data.iter().map(|x| x.to_bits()).collect::<Vec<_>>(), expected.iter().map(|x| x.to_bits()).collect::<Vec<_>>());
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::cmp::Ordering;
+
+use crate::sorter::Sorter;
+
+/// Gap sequences for shell sort. Each yields the gaps below `n` in decreasing
+/// order, always ending with 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gaps {
+    /// n/2, n/4, ..., 1. Quadratic in the worst case.
+    Shell,
+    /// (3^k - 1) / 2: 1, 4, 13, 40, ...
+    Knuth,
+    /// 4^k + 3 * 2^(k-1) + 1: 1, 8, 23, 77, 281, ...
+    Sedgewick,
+    /// Ciura's empirically tuned 1, 4, 10, 23, 57, 132, 301, 701, extended by x2.25.
+    Ciura,
+    /// ceil((9^k - 4^k) / (5 * 4^(k-1))): 1, 4, 9, 20, 46, 103, ...
+    Tokuda,
+}
+
+impl Gaps {
+    pub fn sequence(self, n: usize) -> Vec<usize> {
+        let mut gaps: Vec<usize> = match self {
+            Gaps::Shell => std::iter::successors(Some(n / 2), |&g| Some(g / 2))
+                .take_while(|&g| g > 0)
+                .collect(),
+            Gaps::Knuth => std::iter::successors(Some(1usize), |&g| g.checked_mul(3)?.checked_add(1))
+                .take_while(|&g| g < n)
+                .collect(),
+            Gaps::Sedgewick => std::iter::once(1)
+                .chain((1..).map_while(|k: u32| {
+                    let four_k = 4usize.checked_pow(k)?;
+                    four_k.checked_add(3 * (1 << (k - 1)) + 1)
+                }))
+                .take_while(|&g| g < n)
+                .collect(),
+            Gaps::Ciura => {
+                const CIURA: [usize; 8] = [1, 4, 10, 23, 57, 132, 301, 701];
+                let mut gaps: Vec<usize> = CIURA.iter().copied().take_while(|&g| g < n).collect();
+                let mut next = 701.0 * 2.25;
+                while (next as usize) < n {
+                    gaps.push(next as usize);
+                    next *= 2.25;
+                }
+                gaps
+            }
+            Gaps::Tokuda => {
+                let mut gaps = Vec::new();
+                let mut h = 1.0f64;
+                loop {
+                    let gap = h.ceil() as usize;
+                    if gap >= n && !gaps.is_empty() {
+                        break;
+                    }
+                    gaps.push(gap);
+                    h = 2.25 * h + 1.0;
+                }
+                gaps
+            }
+        };
+        if gaps.is_empty() {
+            gaps.push(1);
+        }
+        gaps.sort_unstable_by(|a, b| b.cmp(a));
+        gaps.dedup();
+        gaps
+    }
+}
+
+pub fn shell_sort<T: Ord>(arr: &mut [T], gaps: Gaps) {
+    shell_sort_by(arr, gaps, T::cmp);
+}
+
+/// Gapped insertion sort over each gap of the sequence in turn; the final
+/// gap of 1 is a plain insertion sort over nearly sorted data.
+pub fn shell_sort_by<T, F>(arr: &mut [T], gaps: Gaps, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if arr.len() < 2 {
+        return;
+    }
+    for gap in gaps.sequence(arr.len()) {
+        for i in gap..arr.len() {
+            let mut j = i;
+            while j >= gap && compare(&arr[j], &arr[j - gap]) == Ordering::Less {
+                arr.swap(j, j - gap);
+                j -= gap;
+            }
+        }
+    }
+}
+
+pub fn comb_sort<T: Ord>(arr: &mut [T]) {
+    comb_sort_by(arr, T::cmp);
+}
+
+/// Bubble sort with a gap that shrinks by 1.3 each pass, so small values near
+/// the end ("turtles") move forward quickly; finishes with gap-1 passes until
+/// no swap happens.
+pub fn comb_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut gap = arr.len();
+    let mut swapped = true;
+    while gap > 1 || swapped {
+        gap = ((gap as f64 / 1.3) as usize).max(1);
+        swapped = false;
+        for i in 0..arr.len().saturating_sub(gap) {
+            if compare(&arr[i + gap], &arr[i]) == Ordering::Less {
+                arr.swap(i, i + gap);
+                swapped = true;
+            }
+        }
+    }
+}
+
+pub struct ShellSort {
+    pub gaps: Gaps,
+}
+
+impl<T: Ord> Sorter<T> for ShellSort {
+    fn name(&self) -> &'static str {
+        match self.gaps {
+            Gaps::Shell => "Shell Sort (Shell)",
+            Gaps::Knuth => "Shell Sort (Knuth)",
+            Gaps::Sedgewick => "Shell Sort (Sedgewick)",
+            Gaps::Ciura => "Shell Sort (Ciura)",
+            Gaps::Tokuda => "Shell Sort (Tokuda)",
+        }
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        shell_sort(data, self.gaps);
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+}
+
+pub struct CombSort;
+
+impl<T: Ord> Sorter<T> for CombSort {
+    fn name(&self) -> &'static str {
+        "Comb Sort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        comb_sort(data);
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const ALL_GAPS: [Gaps; 5] = [Gaps::Shell, Gaps::Knuth, Gaps::Sedgewick, Gaps::Ciura, Gaps::Tokuda];
+
+    fn ascending(gaps: Gaps, n: usize) -> Vec<usize> {
+        let mut seq = gaps.sequence(n);
+        seq.reverse();
+        seq
+    }
+
+    #[test]
+    fn sequences_follow_the_documented_formulas() {
+        assert_eq!(ascending(Gaps::Knuth, 1000), [1, 4, 13, 40, 121, 364]);
+        assert_eq!(ascending(Gaps::Sedgewick, 1000), [1, 8, 23, 77, 281]);
+        assert_eq!(ascending(Gaps::Tokuda, 1000), [1, 4, 9, 20, 46, 103, 233, 525]);
+        assert_eq!(ascending(Gaps::Shell, 100), [1, 3, 6, 12, 25, 50]);
+        // Past 701 Ciura's table is extended by repeated x2.25, truncated.
+        assert_eq!(ascending(Gaps::Ciura, 10_000), [1, 4, 10, 23, 57, 132, 301, 701, 1577, 3548, 7984]);
+    }
+
+    #[test]
+    fn sequences_are_decreasing_below_n_and_end_with_one() {
+        for gaps in ALL_GAPS {
+            for n in [0, 1, 2, 5, 100, 1 << 20] {
+                let seq = gaps.sequence(n);
+                assert_eq!(seq.last(), Some(&1), "{:?} for n = {}", gaps, n);
+                assert!(seq.windows(2).all(|w| w[0] > w[1]), "{:?} for n = {}", gaps, n);
+                assert!(seq.iter().all(|&g| g == 1 || g < n), "{:?} for n = {}", gaps, n);
+            }
+        }
+    }
+
+    fn inputs() -> Vec<Vec<i32>> {
+        let mut rng = StdRng::seed_from_u64(12);
+        vec![
+            vec![],
+            vec![1],
+            vec![2, 1],
+            (0..1000).map(|_| rng.gen_range(-500..500)).collect(),
+            (0..1000).map(|_| rng.gen_range(0..3)).collect(),
+            (0..1000).collect(),
+            (0..1000).rev().collect(),
+        ]
+    }
+
+    #[test]
+    fn every_gap_sequence_sorts() {
+        for input in inputs() {
+            let mut expected = input.clone();
+            expected.sort();
+            for gaps in ALL_GAPS {
+                let mut data = input.clone();
+                shell_sort(&mut data, gaps);
+                assert_eq!(data, expected, "{:?} on {} elements", gaps, input.len());
+            }
+        }
+    }
+
+    #[test]
+    fn comb_sort_sorts() {
+        for mut data in inputs() {
+            let mut expected = data.clone();
+            expected.sort();
+            comb_sort(&mut data);
+            assert_eq!(data, expected);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::error::Error;
+
+use crate::bucket::BucketSort;
+use crate::counting::{CountingSort, PigeonholeSort, DEFAULT_MEMORY_BUDGET};
+use crate::cutoffs::Cutoffs;
+use crate::heap::HeapSort;
+use crate::insertion::{BinaryInsertionSort, InsertionSort};
+use crate::merge::{BottomUpMergeSort, BufferedMergeSort, HybridMergeSort, MergeSort};
+use crate::parallel::{ParallelMergeSort, ParallelRadixSort};
+use crate::quick::{HybridQuickSort, IntroSort, QuickSort};
+use crate::radix::{CountingRadixSort, RadixKey, RadixSort};
+use crate::selection::SelectionSort;
+use crate::shell::{CombSort, Gaps, ShellSort};
+use crate::timsort::TimSort;
+
+/// Common interface for every algorithm in the crate, so the benchmark and
+/// other callers can treat them uniformly.
+pub trait Sorter<T> {
+    fn name(&self) -> &'static str;
+
+    fn sort(&self, data: &mut [T]);
+
+    /// Like `sort`, but returns an error instead of panicking when the
+    /// algorithm refuses the input, e.g. a counting sort whose key range is
+    /// over its memory budget. `data` is left unchanged on error.
+    fn try_sort(&self, data: &mut [T]) -> Result<(), Box<dyn Error>> {
+        self.sort(data);
+        Ok(())
+    }
+
+    /// Whether equal elements keep their relative order.
+    fn is_stable(&self) -> bool;
+
+    /// Whether the sort works without an auxiliary buffer proportional to the input.
+    fn is_in_place(&self) -> bool;
+}
+
+impl<T, S: Sorter<T> + ?Sized> Sorter<T> for &S {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        (**self).sort(data)
+    }
+
+    fn try_sort(&self, data: &mut [T]) -> Result<(), Box<dyn Error>> {
+        (**self).try_sort(data)
+    }
+
+    fn is_stable(&self) -> bool {
+        (**self).is_stable()
+    }
+
+    fn is_in_place(&self) -> bool {
+        (**self).is_in_place()
+    }
+}
+
+pub struct Registry<T> {
+    sorters: Vec<Box<dyn Sorter<T>>>,
+}
+
+impl<T> Registry<T> {
+    pub fn new() -> Self {
+        Registry { sorters: Vec::new() }
+    }
+
+    pub fn register<S: Sorter<T> + 'static>(&mut self, sorter: S) -> &mut Self {
+        self.sorters.push(Box::new(sorter));
+        self
+    }
+
+    /// Looser lookup for user input: ignores case, spaces and punctuation, and
+    /// lets the trailing "sort" be left off, so "radix" finds "Radix Sort" and
+    /// "quicksort-hoare" finds "Quicksort (Hoare)".
+    pub fn lookup(&self, query: &str) -> Option<&dyn Sorter<T>> {
+        let normalize = |s: &str| -> String {
+            s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+        };
+        let query = normalize(query);
+        self.iter().find(|s| {
+            let name = normalize(s.name());
+            name == query || name == format!("{}sort", query)
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Sorter<T>> {
+        self.sorters.iter().map(|s| s.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.sorters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sorters.is_empty()
+    }
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Every `i64` sorter in the crate, in the order the benchmark runs them.
+pub fn default_registry() -> Registry<i64> {
+    registry_with_cutoffs(&Cutoffs::default())
+}
+
+/// Like `default_registry` for any integer key type, with the hybrid sorts
+/// using the given cutoffs.
+pub fn registry_with_cutoffs<T>(cutoffs: &Cutoffs) -> Registry<T>
+where
+    T: RadixKey + Ord + Clone + Send + Sync + 'static,
+{
+    let mut registry = Registry::new();
+    registry
+        .register(RadixSort { radix: 10 })
+        .register(RadixSort { radix: 16 })
+        .register(RadixSort { radix: 256 })
+        .register(RadixSort { radix: 65536 })
+        .register(CountingRadixSort { radix: 10 })
+        .register(CountingRadixSort { radix: 256 });
+    add_comparison_sorts(&mut registry, cutoffs);
+    registry
+        .register(ParallelRadixSort::new(256))
+        .register(BucketSort::new(10))
+        .register(CountingSort { memory_budget: DEFAULT_MEMORY_BUDGET })
+        .register(PigeonholeSort { memory_budget: DEFAULT_MEMORY_BUDGET });
+    registry
+}
+
+/// The sorters that only need `Ord`, for keys that are not integers.
+pub fn comparison_registry<T>(cutoffs: &Cutoffs) -> Registry<T>
+where
+    T: Ord + Clone + Send,
+{
+    let mut registry = Registry::new();
+    add_comparison_sorts(&mut registry, cutoffs);
+    registry
+}
+
+fn add_comparison_sorts<T>(registry: &mut Registry<T>, cutoffs: &Cutoffs)
+where
+    T: Ord + Clone + Send,
+{
+    registry
+        .register(SelectionSort)
+        .register(InsertionSort)
+        .register(BinaryInsertionSort)
+        .register(ShellSort { gaps: Gaps::Shell })
+        .register(ShellSort { gaps: Gaps::Knuth })
+        .register(ShellSort { gaps: Gaps::Sedgewick })
+        .register(ShellSort { gaps: Gaps::Ciura })
+        .register(ShellSort { gaps: Gaps::Tokuda })
+        .register(CombSort)
+        .register(MergeSort)
+        .register(BufferedMergeSort)
+        .register(BottomUpMergeSort)
+        .register(HybridMergeSort { cutoff: cutoffs.merge })
+        .register(TimSort)
+        .register(ParallelMergeSort::new())
+        .register(QuickSort::LOMUTO)
+        .register(QuickSort::HOARE)
+        .register(QuickSort::THREE_WAY)
+        .register(QuickSort::MEDIAN_OF_THREE)
+        .register(QuickSort::RANDOM)
+        .register(HybridQuickSort { cutoff: cutoffs.quick })
+        .register(IntroSort)
+        .register(HeapSort::<2>)
+        .register(HeapSort::<4>);
+}
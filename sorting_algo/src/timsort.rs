@@ -0,0 +1,354 @@
+use std::cmp::Ordering;
+
+use crate::insertion::insertion_sort_by;
+use crate::sorter::Sorter;
+
+/// Consecutive wins by one side before the merge switches to galloping.
+const MIN_GALLOP: usize = 7;
+
+pub fn timsort<T: Ord + Clone>(v: &mut [T]) {
+    timsort_by(v, T::cmp);
+}
+
+/// Adaptive, stable merge sort in the style of Timsort: the input is split
+/// into natural runs (descending runs are reversed), short runs are extended to
+/// `minrun` with insertion sort, and runs are merged with galloping while the
+/// run stack keeps the Timsort length invariants.
+pub fn timsort_by<T, F>(v: &mut [T], mut compare: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = v.len();
+    if len < 2 {
+        return;
+    }
+    let min_run = compute_min_run(len);
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut buffer: Vec<T> = Vec::with_capacity(len / 2);
+    let mut min_gallop = MIN_GALLOP;
+
+    let mut start = 0;
+    while start < len {
+        let mut run_len = count_run(&mut v[start..], &mut compare);
+        if run_len < min_run {
+            let end = (start + min_run).min(len);
+            insertion_sort_by(&mut v[start..end], |a, b| compare(a, b));
+            run_len = end - start;
+        }
+        runs.push((start, run_len));
+        start += run_len;
+        merge_collapse(v, &mut runs, &mut buffer, &mut min_gallop, &mut compare);
+    }
+    while runs.len() > 1 {
+        let n = runs.len() - 2;
+        merge_at(v, &mut runs, n, &mut buffer, &mut min_gallop, &mut compare);
+    }
+}
+
+/// Picks a run length in `32..=64` such that `len / min_run` is a power of two
+/// or slightly less, which keeps the final merges balanced.
+fn compute_min_run(mut len: usize) -> usize {
+    let mut r = 0;
+    while len >= 64 {
+        r |= len & 1;
+        len >>= 1;
+    }
+    len + r
+}
+
+/// Length of the run starting at `v[0]`, reversing it first if it is strictly
+/// descending (strictness keeps the reversal stable).
+fn count_run<T, F>(v: &mut [T], compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if v.len() < 2 {
+        return v.len();
+    }
+    let mut end = 2;
+    if compare(&v[1], &v[0]) == Ordering::Less {
+        while end < v.len() && compare(&v[end], &v[end - 1]) == Ordering::Less {
+            end += 1;
+        }
+        v[..end].reverse();
+    } else {
+        while end < v.len() && compare(&v[end], &v[end - 1]) != Ordering::Less {
+            end += 1;
+        }
+    }
+    end
+}
+
+fn merge_collapse<T, F>(
+    v: &mut [T],
+    runs: &mut Vec<(usize, usize)>,
+    buffer: &mut Vec<T>,
+    min_gallop: &mut usize,
+    compare: &mut F,
+) where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while runs.len() > 1 {
+        let n = runs.len() - 2;
+        let len = |i: usize| runs[i].1;
+        if (n > 0 && len(n - 1) <= len(n) + len(n + 1)) || (n > 1 && len(n - 2) <= len(n - 1) + len(n)) {
+            if len(n - 1) < len(n + 1) {
+                merge_at(v, runs, n - 1, buffer, min_gallop, compare);
+            } else {
+                merge_at(v, runs, n, buffer, min_gallop, compare);
+            }
+        } else if len(n) <= len(n + 1) {
+            merge_at(v, runs, n, buffer, min_gallop, compare);
+        } else {
+            break;
+        }
+    }
+}
+
+/// Merges runs `i` and `i + 1` on the stack.
+fn merge_at<T, F>(
+    v: &mut [T],
+    runs: &mut Vec<(usize, usize)>,
+    i: usize,
+    buffer: &mut Vec<T>,
+    min_gallop: &mut usize,
+    compare: &mut F,
+) where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let (base, len1) = runs[i];
+    let len2 = runs[i + 1].1;
+    runs[i] = (base, len1 + len2);
+    runs.remove(i + 1);
+
+    let run = &mut v[base..base + len1 + len2];
+    // Elements of the left run already <= the right run's first element stay put.
+    let skip = gallop(&run[..len1], &run[len1], false, compare);
+    if skip == len1 {
+        return;
+    }
+    merge_lo(&mut run[skip..], len1 - skip, buffer, min_gallop, compare);
+}
+
+/// Stable merge of `v[..mid]` and `v[mid..]`, copying only the left run out.
+/// After `MIN_GALLOP` consecutive wins by one side it gallops, copying whole
+/// blocks found by exponential search.
+fn merge_lo<T, F>(v: &mut [T], mid: usize, buffer: &mut Vec<T>, min_gallop: &mut usize, compare: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    buffer.clear();
+    buffer.extend_from_slice(&v[..mid]);
+    let left = &buffer[..];
+    let len = v.len();
+    // The write position never passes the unread part of the right run.
+    let (mut i, mut j, mut k) = (0, mid, 0);
+
+    while i < left.len() && j < len {
+        let (mut left_wins, mut right_wins) = (0, 0);
+        while i < left.len() && j < len && left_wins.max(right_wins) < *min_gallop {
+            if compare(&v[j], &left[i]) == Ordering::Less {
+                v[k] = v[j].clone();
+                j += 1;
+                right_wins += 1;
+                left_wins = 0;
+            } else {
+                v[k] = left[i].clone();
+                i += 1;
+                left_wins += 1;
+                right_wins = 0;
+            }
+            k += 1;
+        }
+
+        while i < left.len() && j < len {
+            let from_left = gallop(&left[i..], &v[j], false, compare);
+            v[k..k + from_left].clone_from_slice(&left[i..i + from_left]);
+            i += from_left;
+            k += from_left;
+            if i == left.len() {
+                break;
+            }
+            let from_right = gallop(&v[j..], &left[i], true, compare);
+            for _ in 0..from_right {
+                v[k] = v[j].clone();
+                j += 1;
+                k += 1;
+            }
+            if from_left < MIN_GALLOP && from_right < MIN_GALLOP {
+                *min_gallop += 1;
+                break;
+            }
+            *min_gallop = min_gallop.saturating_sub(1).max(1);
+        }
+    }
+    v[k..k + left.len() - i].clone_from_slice(&left[i..]);
+}
+
+/// Number of leading elements of sorted `v` that go before `key`: those
+/// `< key` when `strict`, otherwise those `<= key`. Probes 1, 3, 7, ... before
+/// binary searching, so it is cheap when the answer is small.
+fn gallop<T, F>(v: &[T], key: &T, strict: bool, compare: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut before = |x: &T| match compare(x, key) {
+        Ordering::Less => true,
+        Ordering::Equal => !strict,
+        Ordering::Greater => false,
+    };
+    let mut bound = 1;
+    while bound <= v.len() && before(&v[bound - 1]) {
+        bound *= 2;
+    }
+    let lo = bound / 2;
+    let hi = bound.min(v.len());
+    lo + v[lo..hi].partition_point(before)
+}
+
+pub struct TimSort;
+
+impl<T: Ord + Clone> Sorter<T> for TimSort {
+    fn name(&self) -> &'static str {
+        "Timsort"
+    }
+
+    fn sort(&self, data: &mut [T]) {
+        timsort(data);
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Sorts `(key, index)` pairs by key alone and checks the result against
+    /// the stable `slice::sort_by_key`, so any reordering of equal keys shows.
+    fn check(keys: &[i64]) {
+        let mut pairs: Vec<(i64, usize)> = keys.iter().copied().zip(0..).collect();
+        let mut expected = pairs.clone();
+        expected.sort_by_key(|&(key, _)| key);
+        timsort_by(&mut pairs, |a, b| a.0.cmp(&b.0));
+        assert_eq!(pairs, expected, "input of length {}", keys.len());
+    }
+
+    #[test]
+    fn random_data() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for len in [0, 1, 2, 31, 64, 65, 1000, 10_000] {
+            let keys: Vec<i64> = (0..len).map(|_| rng.gen_range(-1000..1000)).collect();
+            check(&keys);
+        }
+    }
+
+    #[test]
+    fn sorted_reversed_and_sawtooth_runs() {
+        let n = 5000;
+        check(&(0..n).collect::<Vec<_>>());
+        check(&(0..n).rev().collect::<Vec<_>>());
+        // Descending runs with repeated keys are only reversed up to the first
+        // tie, which keeps the reversal stable.
+        check(&(0..n).map(|i| (n - i) / 3).collect::<Vec<_>>());
+        for teeth in [2, 7, 50, 300] {
+            let period = n / teeth;
+            check(&(0..n).map(|i| i % period).collect::<Vec<_>>());
+            check(&(0..n).map(|i| period - i % period).collect::<Vec<_>>());
+        }
+        // Alternating ascending and descending runs of uneven lengths.
+        let mut keys = Vec::new();
+        for (run, len) in [100, 3, 250, 40, 900, 17, 64, 500].into_iter().enumerate() {
+            let base = (run as i64 * 37) % 200;
+            keys.extend((0..len).map(|i| if run % 2 == 0 { base + i } else { base + len - i }));
+        }
+        check(&keys);
+    }
+
+    #[test]
+    fn long_runs_trigger_galloping() {
+        // Two runs each far longer than MIN_GALLOP whose blocks interleave in
+        // long stretches, so the merge switches to galloping on both sides.
+        let block = (MIN_GALLOP * 20) as i64;
+        let left: Vec<i64> = (0..10).flat_map(|b| (0..block).map(move |i| 2 * b * block + i)).collect();
+        let right: Vec<i64> = (0..10).flat_map(|b| (0..block).map(move |i| (2 * b + 1) * block + i)).collect();
+        check(&[left.clone(), right.clone()].concat());
+        check(&[right, left].concat());
+
+        // Equal keys across the runs, where `gallop` must not let a right
+        // element pass an equal left one.
+        let mut keys: Vec<i64> = (0..2000).map(|i| i / 100).collect();
+        keys.extend((0..2000).map(|i| i / 100));
+        check(&keys);
+    }
+
+    #[test]
+    fn many_equal_keys() {
+        let mut rng = StdRng::seed_from_u64(2);
+        check(&vec![7; 3000]);
+        for distinct in [2, 5, 30] {
+            let keys: Vec<i64> = (0..5000).map(|_| rng.gen_range(0..distinct)).collect();
+            check(&keys);
+        }
+    }
+
+    #[test]
+    fn gallop_strictness() {
+        let v = [1, 2, 2, 2, 3, 5, 8, 8, 9];
+        let mut cmp = i32::cmp;
+        assert_eq!(gallop(&v, &2, true, &mut cmp), 1);
+        assert_eq!(gallop(&v, &2, false, &mut cmp), 4);
+        assert_eq!(gallop(&v, &0, false, &mut cmp), 0);
+        assert_eq!(gallop(&v, &9, true, &mut cmp), 8);
+        assert_eq!(gallop(&v, &9, false, &mut cmp), 9);
+        assert_eq!(gallop(&v, &100, true, &mut cmp), 9);
+        assert_eq!(gallop(&[] as &[i32], &1, true, &mut cmp), 0);
+    }
+
+    #[test]
+    fn merge_collapse_keeps_the_run_invariants() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let lengths: Vec<usize> = (0..200).map(|_| rng.gen_range(1..300)).collect();
+        let mut v: Vec<u32> = lengths.iter().flat_map(|&len| 0..len as u32).collect();
+        let (mut buffer, mut min_gallop, mut cmp) = (Vec::new(), MIN_GALLOP, u32::cmp);
+        let mut runs = Vec::new();
+        let mut start = 0;
+        for len in lengths {
+            runs.push((start, len));
+            start += len;
+            merge_collapse(&mut v, &mut runs, &mut buffer, &mut min_gallop, &mut cmp);
+            let lens: Vec<usize> = runs.iter().map(|r| r.1).collect();
+            for i in 0..lens.len().saturating_sub(1) {
+                assert!(lens[i] > lens[i + 1], "run lengths {:?}", lens);
+                if i + 2 < lens.len() {
+                    assert!(lens[i] > lens[i + 1] + lens[i + 2], "run lengths {:?}", lens);
+                }
+            }
+            for &(base, len) in &runs {
+                assert!(v[base..base + len].windows(2).all(|w| w[0] <= w[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn min_run_is_between_32_and_64() {
+        assert_eq!(compute_min_run(10), 10);
+        assert_eq!(compute_min_run(64), 32);
+        assert_eq!(compute_min_run(65), 33);
+        for len in 64..5000 {
+            assert!((32..=64).contains(&compute_min_run(len)), "{}", len);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Checks that an external sort stays within its memory budget. This lives in
+//! its own test binary because it installs a counting global allocator, and
+//! the peak it records would include allocations from any concurrent test.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sorting_algo::external::{ExternalSort, Format};
+use sorting_algo::quick::IntroSort;
+
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const BUDGET: usize = 2 << 20;
+
+// The peak is process-wide, so the tests take turns.
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// Writes `count` random values to a fresh file in `format`, sorts it with a
+/// budget of `BUDGET` and returns the runs made and the peak heap usage of
+/// the sort alone.
+fn sort_with_budget(name: &str, format: Format, count: usize) -> (usize, usize) {
+    let _turn = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = std::env::temp_dir().join(format!("sorting_algo_test_budget_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let (input, output) = (dir.join("in"), dir.join("out"));
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut writer = BufWriter::new(File::create(&input).unwrap());
+    for i in 0..count {
+        let value: i64 = rng.gen();
+        match format {
+            Format::Binary => writer.write_all(&value.to_le_bytes()).unwrap(),
+            // Everything on one line, so a line-at-a-time reader would hold it all.
+            Format::Text => write!(writer, "{}{}", if i == 0 { "" } else { " " }, value).unwrap(),
+        }
+    }
+    writer.flush().unwrap();
+    drop(writer);
+
+    let sorter = IntroSort;
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let stats = ExternalSort::new(&sorter)
+        .memory_budget(BUDGET)
+        .format(format)
+        .temp_dir(&dir)
+        .sort_file(&input, &output)
+        .unwrap();
+    let used = PEAK.load(Ordering::Relaxed) - baseline;
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(stats.elements, count as u64);
+    (stats.runs, used)
+}
+
+// The chunk itself plus the input and run I/O buffers; the merge splits the
+// budget between its buffers and its heap has one entry per run.
+fn assert_within_budget(used: usize) {
+    assert!(used <= BUDGET + BUDGET / 4, "peak heap usage {} bytes with a budget of {}", used, BUDGET);
+}
+
+#[test]
+fn heap_usage_stays_within_the_budget() {
+    // Eight chunks of BUDGET bytes.
+    let (runs, used) = sort_with_budget("binary", Format::Binary, 8 * (BUDGET / 8));
+    assert_eq!(runs, 8);
+    assert_within_budget(used);
+}
+
+#[test]
+fn single_line_text_stays_within_the_budget() {
+    // About 20 bytes per value, so the line is several times the budget.
+    let (runs, used) = sort_with_budget("text", Format::Text, 4 * (BUDGET / 8));
+    assert_eq!(runs, 4);
+    assert_within_budget(used);
+}
@@ -0,0 +1,101 @@
+//! Runs the `sort` binary on small inputs to check each command-line option.
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sort"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start sort");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn sort(args: &[&str], input: &str) -> String {
+    let output = run(args, input);
+    assert!(output.status.success(), "sort {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn lexicographic_by_default() {
+    assert_eq!(sort(&[], "pear\napple\n10\n9\n"), "10\n9\napple\npear\n");
+}
+
+#[test]
+fn numeric_uses_the_leading_number() {
+    let input = "10 apples\n9 pears\n100\t/usr\n";
+    for algo in ["merge", "radix", "introsort", "counting", "pigeonhole", "bucket"] {
+        assert_eq!(sort(&["-n", "-a", algo], input), "9 pears\n10 apples\n100\t/usr\n", "--algo {}", algo);
+    }
+    assert_eq!(sort(&["--numeric"], "2.5 kg\n-1.5\nabc\n2\n"), "-1.5\nabc\n2\n2.5 kg\n");
+}
+
+#[test]
+fn reverse() {
+    assert_eq!(sort(&["-r"], "b\nc\na\n"), "c\nb\na\n");
+    assert_eq!(sort(&["-n", "--reverse"], "10\n9\n100\n"), "100\n10\n9\n");
+}
+
+#[test]
+fn unique_keeps_the_first_of_equal_keys() {
+    assert_eq!(sort(&["-u"], "b\na\nb\na\n"), "a\nb\n");
+    assert_eq!(sort(&["-n", "-u"], "1 x\n02\n1 a\n2\n"), "1 a\n02\n");
+}
+
+#[test]
+fn stable_keeps_input_order_of_equal_keys() {
+    let input = "2 b\n1 z\n2 a\n1 y\n";
+    assert_eq!(sort(&["-k", "1"], input), "1 y\n1 z\n2 a\n2 b\n");
+    assert_eq!(sort(&["-s", "-k", "1"], input), "1 z\n1 y\n2 b\n2 a\n");
+    assert_eq!(sort(&["-s", "-n", "-k", "1"], input), "1 z\n1 y\n2 b\n2 a\n");
+}
+
+#[test]
+fn key_and_separator() {
+    assert_eq!(sort(&["-k", "2"], "x 3\ny 1\nz 2\n"), "y 1\nz 2\nx 3\n");
+    assert_eq!(sort(&["-t", ",", "-k", "2", "-n"], "a,10,x\nb,9,y\nc,100,z\n"), "b,9,y\na,10,x\nc,100,z\n");
+    // A missing field is empty and sorts first.
+    assert_eq!(sort(&["--separator", ":", "--key", "3"], "a:b:c\nd\n"), "d\na:b:c\n");
+}
+
+#[test]
+fn combined_short_flags() {
+    assert_eq!(sort(&["-nru"], "1\n3\n2\n3\n"), "3\n2\n1\n");
+}
+
+#[test]
+fn reads_files_and_stdin() {
+    let path = std::env::temp_dir().join(format!("sorting_algo_sort_cli_{}.txt", std::process::id()));
+    std::fs::write(&path, "c\na\n").unwrap();
+    let result = sort(&[path.to_str().unwrap(), "-"], "b\n");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(result, "a\nb\nc\n");
+}
+
+#[test]
+fn list_and_help() {
+    // Without -n only the comparison sorts can order lines.
+    let list = sort(&["-l"], "");
+    assert!(list.lines().any(|name| name == "Merge Sort"), "{}", list);
+    assert!(!list.contains("Radix Sort") && !list.contains("Counting Sort"), "{}", list);
+    // With it the integer sorts join them, marked; -l may come first.
+    let list = sort(&["-l", "-n"], "");
+    assert!(list.lines().any(|name| name == "Merge Sort"), "{}", list);
+    assert!(list.lines().any(|name| name == "Radix Sort (-n only)"), "{}", list);
+    assert!(list.lines().any(|name| name == "Pigeonhole Sort (-n only)"), "{}", list);
+    assert!(sort(&["--help"], "").starts_with("usage: sort"));
+}
+
+#[test]
+fn bad_arguments_exit_with_status_two() {
+    for args in [&["--bogus"][..], &["-k", "0"], &["-t", "ab"], &["-a", "nonsense"], &["-k"]] {
+        let output = run(args, "a\n");
+        assert_eq!(output.status.code(), Some(2), "sort {:?}", args);
+        assert!(output.stdout.is_empty());
+    }
+}